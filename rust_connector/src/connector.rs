@@ -0,0 +1,430 @@
+//! Per-venue behavior behind a single trait, so adding a connector is a new `impl Connector` block
+//! instead of a new arm in four different `if lower.contains(...)` chains. Mirrors how xmr-btc-swap
+//! factors its rate providers behind a `LatestRate` trait with swappable `Kraken`/`FixedRate`
+//! impls: `ExchangeConnector` holds a `Arc<dyn Connector>` and everything else - REST fetch,
+//! streaming, synthetic fallback - is generic over the trait.
+
+use crate::local_book::{
+    apply_binance_message, apply_coinbase_message, apply_kraken_message, fetch_binance_snapshot,
+    fetch_kraken_pair_decimals, BinanceOutcome, KrakenOutcome, LocalOrderBook,
+    DEFAULT_PRICE_DECIMALS, DEFAULT_VOLUME_DECIMALS,
+};
+use crate::OrderBook;
+use async_trait::async_trait;
+use serde_json::Value;
+
+/// Result of folding one websocket text frame into a connector's local book.
+pub enum WsOutcome {
+    Updated(OrderBook),
+    /// The book failed an integrity check (Kraken checksum, Binance `U`/`u` gap) and must be
+    /// dropped and re-seeded; the caller reconnects and retries rather than serving stale data.
+    Resync,
+    /// A recognized non-book control frame (Kraken `heartbeat`/`systemStatus`/
+    /// `subscriptionStatus`, Coinbase `heartbeat`/`subscriptions`) - surfaced so the stream
+    /// driver can log it instead of lumping it in with genuinely unrecognized frames.
+    Control(&'static str),
+    /// Not a recognized message at all.
+    Ignored,
+}
+
+#[async_trait]
+pub trait Connector: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    fn list_symbols(&self) -> Vec<String>;
+
+    fn rest_depth_url(&self, symbol: &str) -> String;
+
+    fn parse_rest(&self, v: &Value) -> OrderBook;
+
+    fn ws_url(&self, symbol: &str) -> String;
+
+    /// Subscription payload to send right after connecting, if the venue needs one (Binance's
+    /// stream path encodes the symbol in the URL and needs none).
+    fn subscribe_msg(&self, symbol: &str) -> Option<String> {
+        let _ = symbol;
+        None
+    }
+
+    /// A fresh local book, configured the way this venue needs (checksum decimals, etc).
+    fn new_book(&self) -> LocalOrderBook {
+        LocalOrderBook::new(DEFAULT_PRICE_DECIMALS, DEFAULT_VOLUME_DECIMALS)
+    }
+
+    fn parse_ws(&self, book: &mut LocalOrderBook, text: &str) -> WsOutcome;
+
+    /// Fetches whatever out-of-band state is needed to (re)seed `book` before streaming starts.
+    /// Most venues snapshot from their own websocket feed; only Binance needs a separate REST
+    /// call first.
+    async fn seed(&self, symbol: &str, book: &mut LocalOrderBook) -> Result<(), String> {
+        let _ = (symbol, book);
+        Ok(())
+    }
+
+    /// `true` for connectors with no live feed (`Mock`, `Uniswap` today): `start_stream` and
+    /// `fetch_orderbook_sync` skip REST/websocket entirely and use `synthetic_tick`/
+    /// `synthetic_snapshot` instead.
+    fn is_synthetic(&self) -> bool {
+        false
+    }
+
+    /// `true` only for `ReplayConnector`: `start_stream` replays `replay_path` instead of
+    /// opening a live websocket or generating a synthetic tick.
+    fn is_replay(&self) -> bool {
+        false
+    }
+
+    /// Path to the newline-delimited `{ts, bids, asks}` capture `ReplayConnector` reads back.
+    fn replay_path(&self) -> &str {
+        ""
+    }
+
+    /// Playback speed multiplier: `1.0` replays at the capture's original pace, `0.0` disables
+    /// inter-frame sleeping entirely (as fast as possible).
+    fn replay_speed(&self) -> f64 {
+        1.0
+    }
+
+    fn synthetic_snapshot(&self) -> OrderBook {
+        let mid = 100.0;
+        let spread = 0.001;
+        let bid = mid * (1.0 - spread / 2.0);
+        let ask = mid * (1.0 + spread / 2.0);
+        OrderBook::new(vec![(bid, 1.0), (bid * 0.999, 2.0)], vec![(ask, 1.0), (ask * 1.001, 2.0)])
+    }
+
+    fn synthetic_tick(&self) -> OrderBook {
+        let mid = 100.0 + (fastrand::f64() - 0.5) * 0.5;
+        let spread = 0.001;
+        let bid = (mid * (1.0 - spread / 2.0) * 1e8f64).round() / 1e8f64;
+        let ask = (mid * (1.0 + spread / 2.0) * 1e8f64).round() / 1e8f64;
+        OrderBook::new(vec![(bid, 1.0), (bid * 0.999, 2.0)], vec![(ask, 1.0), (ask * 1.001, 2.0)])
+    }
+}
+
+fn parse_levels_take5(v: &Value, key: &str) -> Vec<(f64, f64)> {
+    v.get(key)
+        .and_then(|x| x.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|it| {
+                    let p = it.get(0)?.as_str()?.parse::<f64>().ok()?;
+                    let q = it.get(1)?.as_str()?.parse::<f64>().ok()?;
+                    Some((p, q))
+                })
+                .take(5)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+pub struct BinanceConnector;
+
+#[async_trait]
+impl Connector for BinanceConnector {
+    fn name(&self) -> &'static str {
+        "binance"
+    }
+
+    fn list_symbols(&self) -> Vec<String> {
+        vec!["BTCUSDT".to_string(), "ETHUSDT".to_string(), "BNBUSDT".to_string()]
+    }
+
+    fn rest_depth_url(&self, symbol: &str) -> String {
+        format!("https://api.binance.com/api/v3/depth?symbol={}&limit=5", symbol.to_uppercase())
+    }
+
+    fn parse_rest(&self, v: &Value) -> OrderBook {
+        // Binance's `/depth` response keys are `bids`/`asks` (the `b`/`a` short keys are only
+        // used by the websocket diff-depth frames).
+        OrderBook::new(parse_levels_take5(v, "bids"), parse_levels_take5(v, "asks"))
+    }
+
+    fn ws_url(&self, symbol: &str) -> String {
+        // True diff-depth stream (not the `@depth5` partial-book preview), so `parse_ws` can
+        // maintain a full local book and gap-check `U`/`u`.
+        format!("wss://stream.binance.com:9443/ws/{}@depth@100ms", symbol.to_lowercase())
+    }
+
+    fn new_book(&self) -> LocalOrderBook {
+        // Checksum decimals are a Kraken-only concept; Binance's gap check doesn't use them.
+        LocalOrderBook::with_kraken_defaults()
+    }
+
+    fn parse_ws(&self, book: &mut LocalOrderBook, text: &str) -> WsOutcome {
+        let Ok(v) = serde_json::from_str::<Value>(text) else { return WsOutcome::Ignored };
+        match apply_binance_message(book, &v) {
+            BinanceOutcome::Updated => WsOutcome::Updated(book.to_order_book(5)),
+            BinanceOutcome::Gap(_) => WsOutcome::Resync,
+            BinanceOutcome::Ignored => WsOutcome::Ignored,
+        }
+    }
+
+    async fn seed(&self, symbol: &str, book: &mut LocalOrderBook) -> Result<(), String> {
+        let (last_update_id, bids, asks) = fetch_binance_snapshot(symbol).await?;
+        book.seed_binance_snapshot(last_update_id, &bids, &asks);
+        Ok(())
+    }
+}
+
+pub struct CoinbaseConnector;
+
+#[async_trait]
+impl Connector for CoinbaseConnector {
+    fn name(&self) -> &'static str {
+        "coinbase"
+    }
+
+    fn list_symbols(&self) -> Vec<String> {
+        vec!["BTC-USD".to_string(), "ETH-USD".to_string()]
+    }
+
+    fn rest_depth_url(&self, symbol: &str) -> String {
+        format!("https://api.exchange.coinbase.com/products/{}/book?level=2", symbol)
+    }
+
+    fn parse_rest(&self, v: &Value) -> OrderBook {
+        OrderBook::new(parse_levels_take5(v, "bids"), parse_levels_take5(v, "asks"))
+    }
+
+    fn ws_url(&self, _symbol: &str) -> String {
+        "wss://ws-feed.exchange.coinbase.com".to_string()
+    }
+
+    fn subscribe_msg(&self, symbol: &str) -> Option<String> {
+        Some(
+            serde_json::json!({
+                "type": "subscribe",
+                "channels": [{"name": "level2", "product_ids": [symbol]}]
+            })
+            .to_string(),
+        )
+    }
+
+    fn parse_ws(&self, book: &mut LocalOrderBook, text: &str) -> WsOutcome {
+        let Ok(v) = serde_json::from_str::<Value>(text) else { return WsOutcome::Ignored };
+        match v.get("type").and_then(|t| t.as_str()) {
+            Some("heartbeat") => return WsOutcome::Control("heartbeat"),
+            Some("subscriptions") => return WsOutcome::Control("subscriptions"),
+            _ => {}
+        }
+        if apply_coinbase_message(book, &v) {
+            WsOutcome::Updated(book.to_order_book(5))
+        } else {
+            WsOutcome::Ignored
+        }
+    }
+}
+
+pub struct KrakenConnector;
+
+#[async_trait]
+impl Connector for KrakenConnector {
+    fn name(&self) -> &'static str {
+        "kraken"
+    }
+
+    fn list_symbols(&self) -> Vec<String> {
+        vec!["XBTUSDT".to_string(), "ETHUSDT".to_string(), "XXBTZUSD".to_string()]
+    }
+
+    fn rest_depth_url(&self, symbol: &str) -> String {
+        format!("https://api.kraken.com/0/public/Depth?pair={}&count=5", symbol)
+    }
+
+    fn parse_rest(&self, v: &Value) -> OrderBook {
+        // Kraken response format: {"result": {"XBTUSDT": {"bids": [...], "asks": [...]}}}
+        let pair_data = v.get("result").and_then(|r| r.as_object()).and_then(|obj| obj.values().next());
+        match pair_data {
+            Some(pair_data) => OrderBook::new(parse_levels_take5(pair_data, "bids"), parse_levels_take5(pair_data, "asks")),
+            None => OrderBook::new(vec![], vec![]),
+        }
+    }
+
+    fn ws_url(&self, _symbol: &str) -> String {
+        "wss://ws.kraken.com".to_string()
+    }
+
+    fn subscribe_msg(&self, symbol: &str) -> Option<String> {
+        Some(
+            serde_json::json!({
+                "event": "subscribe",
+                "pair": [symbol],
+                "subscription": {"name": "book", "depth": 10}
+            })
+            .to_string(),
+        )
+    }
+
+    fn new_book(&self) -> LocalOrderBook {
+        LocalOrderBook::with_kraken_defaults()
+    }
+
+    fn parse_ws(&self, book: &mut LocalOrderBook, text: &str) -> WsOutcome {
+        let Ok(v) = serde_json::from_str::<Value>(text) else { return WsOutcome::Ignored };
+        if let Some(event) = v.get("event").and_then(|e| e.as_str()) {
+            match event {
+                "heartbeat" => return WsOutcome::Control("heartbeat"),
+                "systemStatus" => return WsOutcome::Control("systemStatus"),
+                "subscriptionStatus" => return WsOutcome::Control("subscriptionStatus"),
+                _ => {}
+            }
+        }
+        match apply_kraken_message(book, &v) {
+            KrakenOutcome::Updated => WsOutcome::Updated(book.to_order_book(10)),
+            KrakenOutcome::ChecksumMismatch => WsOutcome::Resync,
+            KrakenOutcome::Ignored => WsOutcome::Ignored,
+        }
+    }
+
+    /// Looks up `symbol`'s actual `pair_decimals`/`lot_decimals` via `AssetPairs` and applies
+    /// them to `book` before any deltas are folded in, so `kraken_checksum` matches Kraken's `c`
+    /// field for pairs whose precision isn't the `DEFAULT_PRICE_DECIMALS`/`DEFAULT_VOLUME_DECIMALS`
+    /// fallback `new_book` seeded it with (e.g. ETH/USD's 2-decimal price).
+    async fn seed(&self, symbol: &str, book: &mut LocalOrderBook) -> Result<(), String> {
+        let (price_decimals, volume_decimals) = fetch_kraken_pair_decimals(symbol).await?;
+        book.set_decimals(price_decimals, volume_decimals);
+        Ok(())
+    }
+}
+
+/// Reserves-only venue: `start_stream`/`fetch_orderbook_sync` never hit the DEX directly (see
+/// `uniswap_get_reserves` for that), so it reuses the same synthetic random walk as `Mock` -
+/// exactly what the pre-trait name-dispatch fell back to for any unmatched name, "uniswap"
+/// included.
+pub struct UniswapConnector;
+
+#[async_trait]
+impl Connector for UniswapConnector {
+    fn name(&self) -> &'static str {
+        "uniswap"
+    }
+
+    fn list_symbols(&self) -> Vec<String> {
+        vec!["UNI/ETH".to_string(), "USDC/ETH".to_string()]
+    }
+
+    fn rest_depth_url(&self, _symbol: &str) -> String {
+        String::new()
+    }
+
+    fn parse_rest(&self, _v: &Value) -> OrderBook {
+        self.synthetic_snapshot()
+    }
+
+    fn ws_url(&self, _symbol: &str) -> String {
+        String::new()
+    }
+
+    fn parse_ws(&self, _book: &mut LocalOrderBook, _text: &str) -> WsOutcome {
+        WsOutcome::Ignored
+    }
+
+    fn is_synthetic(&self) -> bool {
+        true
+    }
+}
+
+pub struct MockConnector;
+
+#[async_trait]
+impl Connector for MockConnector {
+    fn name(&self) -> &'static str {
+        "mock"
+    }
+
+    fn list_symbols(&self) -> Vec<String> {
+        vec!["BTC-USD".to_string(), "ETH-USD".to_string()]
+    }
+
+    fn rest_depth_url(&self, _symbol: &str) -> String {
+        String::new()
+    }
+
+    fn parse_rest(&self, _v: &Value) -> OrderBook {
+        self.synthetic_snapshot()
+    }
+
+    fn ws_url(&self, _symbol: &str) -> String {
+        String::new()
+    }
+
+    fn parse_ws(&self, _book: &mut LocalOrderBook, _text: &str) -> WsOutcome {
+        WsOutcome::Ignored
+    }
+
+    fn is_synthetic(&self) -> bool {
+        true
+    }
+}
+
+/// Deterministic backtest source: replays a recorded capture instead of hitting a live feed, the
+/// same role `FixedRate` plays next to the real rate providers in xmr-btc-swap. Built directly
+/// via `get_replay_connector(path, speed)` rather than `build_connector`/`get_connector`, since a
+/// capture path (and optional playback speed) doesn't fit the single-`name` factory.
+pub struct ReplayConnector {
+    path: String,
+    speed: f64,
+}
+
+impl ReplayConnector {
+    pub fn new(path: String, speed: f64) -> Self {
+        ReplayConnector { path, speed: if speed > 0.0 { speed } else { 0.0 } }
+    }
+}
+
+#[async_trait]
+impl Connector for ReplayConnector {
+    fn name(&self) -> &'static str {
+        "replay"
+    }
+
+    fn list_symbols(&self) -> Vec<String> {
+        vec![]
+    }
+
+    fn rest_depth_url(&self, _symbol: &str) -> String {
+        String::new()
+    }
+
+    fn parse_rest(&self, _v: &Value) -> OrderBook {
+        OrderBook::new(vec![], vec![])
+    }
+
+    fn ws_url(&self, _symbol: &str) -> String {
+        String::new()
+    }
+
+    fn parse_ws(&self, _book: &mut LocalOrderBook, _text: &str) -> WsOutcome {
+        WsOutcome::Ignored
+    }
+
+    fn is_replay(&self) -> bool {
+        true
+    }
+
+    fn replay_path(&self) -> &str {
+        &self.path
+    }
+
+    fn replay_speed(&self) -> f64 {
+        self.speed
+    }
+}
+
+/// Chooses a connector the same way `ExchangeConnector::new` always has: substring match on
+/// `name`, falling back to `Mock` for anything unrecognized.
+pub fn build_connector(name: &str) -> std::sync::Arc<dyn Connector> {
+    let lower = name.to_lowercase();
+    if lower.contains("binance") {
+        std::sync::Arc::new(BinanceConnector)
+    } else if lower.contains("coinbase") {
+        std::sync::Arc::new(CoinbaseConnector)
+    } else if lower.contains("kraken") {
+        std::sync::Arc::new(KrakenConnector)
+    } else if lower.contains("uniswap") {
+        std::sync::Arc::new(UniswapConnector)
+    } else {
+        std::sync::Arc::new(MockConnector)
+    }
+}
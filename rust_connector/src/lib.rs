@@ -8,8 +8,26 @@
 //!    - fetch_orderbook_sync(symbol)
 //!    - start_stream(py, symbol, callback)
 //!    - latest_snapshot()
+//!    - status (get-only property)
+//!    - set_on_status(callback)
+//!    - set_record_path(path)
+//! - get_replay_connector(path, speed) â€” ExchangeConnector over a recorded capture
 //! - uniswap_get_reserves(rpc_url, pair_address)
 //! - compute_dex_cex_arbitrage(ob_cex, ob_dex, fee_cex, fee_dex)
+//!
+//! Venue-specific behavior (REST URLs/parsing, websocket URL/subscribe/parsing, the synthetic
+//! fallback) lives behind the `Connector` trait in `connector`, chosen by name in
+//! `connector::build_connector`; `ExchangeConnector` itself is a thin PyO3 wrapper around a
+//! `Arc<dyn Connector>`. Streaming connectors keep a full local book (see `local_book`) rather
+//! than forwarding whatever frame last arrived: Kraken deltas are checksum-verified, Binance
+//! diff-depth frames are gap-checked against `U`/`u`, and on either failure the book is dropped
+//! and re-seeded. `run_connector_stream` never lets the task die on disconnect: it's a supervised
+//! loop that reconnects with backoff+jitter, resubscribes and re-seeds, and surfaces its state
+//! through `ExchangeConnector.status` (`connected`/`reconnecting`/`stale`) plus an optional
+//! `on_status` callback. `set_record_path` tees every streamed `OrderBook` to a capture file, and
+//! `ReplayConnector` (via `get_replay_connector`) reads one back for reproducible backtests - a
+//! capture-then-replay pair, analogous to xmr-btc-swap's live `Kraken` rate provider next to its
+//! deterministic `FixedRate` one.
 
 use pyo3::prelude::*;
 use pyo3::types::PyDict;
@@ -17,11 +35,15 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::sync::{Arc, Mutex};
 use futures_util::{StreamExt, SinkExt};
+use tokio::time::{sleep, timeout, Duration};
 use tokio_tungstenite::connect_async;
 use tokio_tungstenite::tungstenite::Message;
 use env_logger;
 use log::{info, warn};
-use fastrand;
+
+mod local_book;
+mod connector;
+use connector::{build_connector, Connector, WsOutcome};
 
 /// OrderBook struct sent to Python
 #[pyclass]
@@ -50,381 +72,407 @@ impl OrderBook {
 
 type Snapshot = Arc<Mutex<Option<OrderBook>>>;
 
-/// Generic connector. Concrete behavior chosen by name.
-#[pyclass]
-pub struct ExchangeConnector {
-    name: String,
-    snapshot: Snapshot,
+/// Connection state surfaced to Python via `ExchangeConnector.status`, so a strategy can halt
+/// trading while a feed is reconnecting or stale instead of trusting a book that stopped moving.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum StreamStatus {
+    Connected,
+    Reconnecting,
+    Stale,
 }
 
-#[pymethods]
-impl ExchangeConnector {
-    #[new]
-    fn new(name: String) -> Self {
-        let _ = env_logger::try_init();
-        ExchangeConnector {
-            name,
-            snapshot: Arc::new(Mutex::new(None)),
+impl StreamStatus {
+    fn as_str(&self) -> &'static str {
+        match self {
+            StreamStatus::Connected => "connected",
+            StreamStatus::Reconnecting => "reconnecting",
+            StreamStatus::Stale => "stale",
         }
     }
+}
 
-    fn list_symbols(&self) -> PyResult<Vec<String>> {
-        let lower = self.name.to_lowercase();
-        if lower.contains("binance") {
-            Ok(vec!["BTCUSDT".to_string(), "ETHUSDT".to_string(), "BNBUSDT".to_string()])
-        } else if lower.contains("coinbase") {
-            Ok(vec!["BTC-USD".to_string(), "ETH-USD".to_string()])
-        } else if lower.contains("kraken") {
-            Ok(vec!["XBTUSDT".to_string(), "ETHUSDT".to_string(), "XXBTZUSD".to_string()])
-        } else if lower.contains("uniswap") {
-            Ok(vec!["UNI/ETH".to_string(), "USDC/ETH".to_string()])
+type StatusCell = Arc<Mutex<StreamStatus>>;
+type StatusCallback = Arc<Mutex<Option<PyObject>>>;
+/// `Some(path)` once `set_record_path` has been called: every streamed `OrderBook` is appended
+/// there as it arrives, in the same `{ts, bids, asks}` shape `ReplayConnector` reads back.
+type RecordPath = Arc<Mutex<Option<String>>>;
+
+/// One line of a replay capture: `ts` is Unix seconds (matches what `record_tick` writes), so a
+/// capture recorded from a live connector can be fed straight back through `ReplayConnector`.
+#[derive(Deserialize)]
+struct ReplayFrame {
+    ts: f64,
+    bids: Vec<(f64, f64)>,
+    asks: Vec<(f64, f64)>,
+}
+
+fn epoch_seconds() -> f64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs_f64()).unwrap_or(0.0)
+}
+
+/// Appends one streamed `OrderBook` to `record_path`, if set, as a `{ts, bids, asks}` JSON line.
+fn record_tick(record_path: &RecordPath, ob: &OrderBook) {
+    use std::io::Write;
+    let Some(path) = record_path.lock().unwrap().clone() else { return };
+    let line = serde_json::json!({"ts": epoch_seconds(), "bids": ob.bids, "asks": ob.asks}).to_string();
+    match std::fs::OpenOptions::new().create(true).append(true).open(&path) {
+        Ok(mut f) => { let _ = writeln!(f, "{}", line); }
+        Err(e) => warn!("record {}: open error: {:?}", path, e),
+    }
+}
+
+/// No frame (data, heartbeat, or otherwise) within this long means the connection is dead in all
+/// but name; treated the same as a hard disconnect.
+const STALE_TIMEOUT: Duration = Duration::from_secs(30);
+const BASE_BACKOFF: Duration = Duration::from_millis(500);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Exponential backoff off `BASE_BACKOFF`, capped at `MAX_BACKOFF`, with up to 30% jitter so a
+/// mass-disconnect (venue-side blip) doesn't have every reconnecting client retry in lockstep.
+fn backoff_delay(attempt: u32) -> Duration {
+    let capped = BASE_BACKOFF.saturating_mul(1u32 << attempt.min(6)).min(MAX_BACKOFF);
+    capped + Duration::from_millis((fastrand::f64() * capped.as_millis() as f64 * 0.3) as u64)
+}
+
+fn set_status(status: &StatusCell, on_status: &StatusCallback, new: StreamStatus) {
+    let changed = {
+        let mut s = status.lock().unwrap();
+        if *s == new {
+            false
         } else {
-            Ok(vec!["BTC-USD".to_string(), "ETH-USD".to_string()])
+            *s = new;
+            true
+        }
+    };
+    if changed {
+        if let Some(cb) = on_status.lock().unwrap().as_ref() {
+            Python::with_gil(|py| {
+                let _ = cb.call1(py, (new.as_str(),));
+            });
         }
     }
+}
 
-    /// Blocking snapshot via REST for simplicity
-    fn fetch_orderbook_sync(&self, symbol: String) -> PyResult<OrderBook> {
-        let lower = self.name.to_lowercase();
-        if lower.contains("binance") {
-            let url = format!(
-                "https://api.binance.com/api/v3/depth?symbol={}&limit=5",
-                symbol.to_uppercase()
-            );
-            match reqwest::blocking::get(&url) {
-                Ok(resp) => match resp.json::<Value>() {
-                    Ok(v) => {
-                        let bids = parse_binance_rest(&v, "b");
-                        let asks = parse_binance_rest(&v, "a");
-                        return Ok(OrderBook::new(bids, asks));
-                    }
-                    Err(e) => return Err(pyo3::exceptions::PyRuntimeError::new_err(format!("json parse: {:?}", e))),
-                },
-                Err(e) => return Err(pyo3::exceptions::PyRuntimeError::new_err(format!("request: {:?}", e))),
-            }
-        } else if lower.contains("coinbase") {
-            let url = format!("https://api.exchange.coinbase.com/products/{}/book?level=2", symbol);
-            match reqwest::blocking::get(&url) {
-                Ok(resp) => match resp.json::<Value>() {
-                    Ok(v) => {
-                        let bids = parse_coinbase_rest(&v, "bids");
-                        let asks = parse_coinbase_rest(&v, "asks");
-                        return Ok(OrderBook::new(bids, asks));
-                    }
-                    Err(e) => return Err(pyo3::exceptions::PyRuntimeError::new_err(format!("json parse: {:?}", e))),
-                },
-                Err(e) => return Err(pyo3::exceptions::PyRuntimeError::new_err(format!("request: {:?}", e))),
+fn emit(cb: &PyObject, ob: &OrderBook) {
+    Python::with_gil(|py| {
+        let cb_ref = cb.bind(py);
+        if let Ok(py_ob) = Py::new(py, ob.clone()) {
+            let _ = cb_ref.call1((py_ob,));
+        }
+    });
+}
+
+/// Drives a non-synthetic connector's websocket forever: on disconnect, connect error, read
+/// timeout (`STALE_TIMEOUT` with no frame) or `WsOutcome::Resync` (a Kraken checksum mismatch or
+/// a Binance sequence gap), it drops the local book, waits out a backoff, reconnects and
+/// re-subscribes rather than letting the spawned thread die silently. `status`/`on_status` track
+/// the supervised loop's state for Python.
+///
+/// Connects and subscribes *before* fetching the REST snapshot, buffering whatever arrives in
+/// the meantime rather than discarding it - Binance's documented recipe (and the order
+/// `L2Maintainer` already uses for its own `AwaitingSnapshot` buffering). Fetching the snapshot
+/// first would lose every diff emitted between the REST call and the websocket connect, so the
+/// first live frame would always look like a sequence gap and force an immediate resync.
+async fn run_connector_stream(
+    connector: &dyn Connector,
+    symbol: &str,
+    snapshot: &Snapshot,
+    cb: &PyObject,
+    status: &StatusCell,
+    on_status: &StatusCallback,
+    record_path: &RecordPath,
+) {
+    let mut attempt: u32 = 0;
+    loop {
+        set_status(status, on_status, StreamStatus::Reconnecting);
+
+        let url = connector.ws_url(symbol);
+        let (ws_stream, _) = match connect_async(&url).await {
+            Ok(conn) => conn,
+            Err(e) => {
+                warn!("{} connect error: {:?}", connector.name(), e);
+                sleep(backoff_delay(attempt)).await;
+                attempt = attempt.saturating_add(1);
+                continue;
             }
-        } else if lower.contains("kraken") {
-            let url = format!("https://api.kraken.com/0/public/Depth?pair={}&count=5", symbol);
-            match reqwest::blocking::get(&url) {
-                Ok(resp) => match resp.json::<Value>() {
-                    Ok(v) => {
-                        let bids = parse_kraken_rest(&v, "bids");
-                        let asks = parse_kraken_rest(&v, "asks");
-                        return Ok(OrderBook::new(bids, asks));
-                    }
-                    Err(e) => return Err(pyo3::exceptions::PyRuntimeError::new_err(format!("json parse: {:?}", e))),
-                },
-                Err(e) => return Err(pyo3::exceptions::PyRuntimeError::new_err(format!("request: {:?}", e))),
+        };
+        info!("Connected to {} {}", connector.name(), url);
+        let (mut write, mut read) = ws_stream.split();
+        if let Some(sub) = connector.subscribe_msg(symbol) {
+            let _ = write.send(Message::Text(sub)).await;
+        }
+
+        // Buffer whatever arrives while the snapshot fetch is in flight; replayed below once
+        // `seed` returns so nothing between subscribe and snapshot is lost.
+        let mut book = connector.new_book();
+        let mut buffered: Vec<Message> = Vec::new();
+        let seed_result = {
+            let seed_fut = connector.seed(symbol, &mut book);
+            tokio::pin!(seed_fut);
+            loop {
+                tokio::select! {
+                    res = &mut seed_fut => break res,
+                    frame = read.next() => match frame {
+                        Some(Ok(msg)) => { attempt = 0; buffered.push(msg); }
+                        Some(Err(e)) => break Err(format!("ws error while seeding: {:?}", e)),
+                        None => break Err("stream ended while seeding".to_string()),
+                    },
+                }
             }
-        } else {
-            // synthetic fallback
-            let mid = 100.0;
-            let spread = 0.001;
-            let bid = mid * (1.0 - spread / 2.0);
-            let ask = mid * (1.0 + spread / 2.0);
-            let bids = vec![(bid, 1.0), (bid * 0.999, 2.0)];
-            let asks = vec![(ask, 1.0), (ask * 1.001, 2.0)];
-            Ok(OrderBook::new(bids, asks))
+        };
+        if let Err(e) = seed_result {
+            warn!("{} snapshot fetch error: {:?}", connector.name(), e);
+            sleep(backoff_delay(attempt)).await;
+            attempt = attempt.saturating_add(1);
+            continue;
         }
-    }
+        set_status(status, on_status, StreamStatus::Connected);
 
-    /// Start streaming; callback is a Python callable that receives an OrderBook pyobject.
-    /// Spawns a tokio task for async WebSocket handling.
-    fn start_stream(&mut self, _py: Python<'_>, symbol: String, py_callback: PyObject) -> PyResult<()> {
-        let snapshot = self.snapshot.clone();
-        let name = self.name.clone();
-        let cb = py_callback.clone();
+        let mut resync = false;
+        for msg in buffered {
+            if apply_frame(connector, symbol, &mut book, msg, &mut write, snapshot, cb, record_path).await {
+                warn!("{} {}: resyncing", connector.name(), symbol);
+                resync = true;
+                break;
+            }
+        }
 
-        // Spawn in a new thread with its own tokio runtime
-        std::thread::spawn(move || {
-            let rt = tokio::runtime::Runtime::new().expect("Failed to create tokio runtime");
-            rt.block_on(async move {
-            let lower = name.to_lowercase();
-            if lower.contains("binance") {
-                let url = format!("wss://stream.binance.com:9443/ws/{}@depth5@100ms", symbol.to_lowercase());
-                match connect_async(&url).await {
-                    Ok((ws_stream, _)) => {
-                        info!("Connected to Binance {}", url);
-                        let (_write, mut read) = ws_stream.split();
-                        while let Some(msg) = read.next().await {
-                            match msg {
-                                Ok(Message::Text(txt)) => {
-                                    if let Ok(ob) = parse_binance_depth_text(&txt) {
-                                        if let Ok(mut s) = snapshot.lock() { *s = Some(ob.clone()); }
-                                        Python::with_gil(|py| {
-                                            let cb_ref = cb.bind(py);
-                                            if let Ok(py_ob) = Py::new(py, ob.clone()) {
-                                                let _ = cb_ref.call1((py_ob,));
-                                            }
-                                        });
-                                    }
-                                }
-                                Ok(Message::Ping(_)) | Ok(Message::Pong(_)) => {}
-                                Ok(Message::Close(_)) => break,
-                                Err(e) => { warn!("binance ws error: {:?}", e); break; }
-                                _ => {}
-                            }
+        if !resync {
+            loop {
+                match timeout(STALE_TIMEOUT, read.next()).await {
+                    Ok(Some(Ok(msg))) => {
+                        attempt = 0;
+                        if apply_frame(connector, symbol, &mut book, msg, &mut write, snapshot, cb, record_path).await {
+                            warn!("{} {}: resyncing", connector.name(), symbol);
+                            break;
                         }
                     }
-                    Err(e) => warn!("binance connect error: {:?}", e),
-                }
-            } else if lower.contains("coinbase") {
-                let url = "wss://ws-feed.exchange.coinbase.com";
-                match connect_async(url).await {
-                    Ok((ws_stream, _)) => {
-                        info!("Connected to Coinbase WS");
-                        let (mut write, mut read) = ws_stream.split();
-                        let subscribe = serde_json::json!({
-                            "type":"subscribe",
-                            "channels":[{"name":"level2","product_ids":[symbol]}]
-                        });
-                        let _ = write.send(Message::Text(subscribe.to_string())).await;
-                        while let Some(msg) = read.next().await {
-                            match msg {
-                                Ok(Message::Text(txt)) => {
-                                    if let Ok(ob) = parse_coinbase_l2_text(&txt) {
-                                        if let Ok(mut s) = snapshot.lock() { *s = Some(ob.clone()); }
-                                        Python::with_gil(|py| {
-                                            let cb_ref = cb.bind(py);
-                                            if let Ok(py_ob) = Py::new(py, ob.clone()) {
-                                                let _ = cb_ref.call1((py_ob,));
-                                            }
-                                        });
-                                    }
-                                }
-                                _ => {}
-                            }
-                        }
+                    Ok(Some(Err(e))) => {
+                        warn!("{} ws error: {:?}", connector.name(), e);
+                        break;
                     }
-                    Err(e) => warn!("coinbase connect error: {:?}", e),
-                }
-            } else if lower.contains("kraken") {
-                let url = "wss://ws.kraken.com";
-                match connect_async(url).await {
-                    Ok((ws_stream, _)) => {
-                        info!("Connected to Kraken WS");
-                        let (mut write, mut read) = ws_stream.split();
-                        let subscribe = serde_json::json!({
-                            "event": "subscribe",
-                            "pair": [symbol.clone()],
-                            "subscription": {"name": "book", "depth": 10}
-                        });
-                        let _ = write.send(Message::Text(subscribe.to_string())).await;
-                        while let Some(msg) = read.next().await {
-                            match msg {
-                                Ok(Message::Text(txt)) => {
-                                    if let Ok(ob) = parse_kraken_ws_text(&txt) {
-                                        if let Ok(mut s) = snapshot.lock() { *s = Some(ob.clone()); }
-                                        Python::with_gil(|py| {
-                                            let cb_ref = cb.bind(py);
-                                            if let Ok(py_ob) = Py::new(py, ob.clone()) {
-                                                let _ = cb_ref.call1((py_ob,));
-                                            }
-                                        });
-                                    }
-                                }
-                                _ => {}
-                            }
-                        }
+                    Ok(None) => break,
+                    Err(_) => {
+                        warn!("{} {}: no frame within {:?}, reconnecting", connector.name(), symbol, STALE_TIMEOUT);
+                        set_status(status, on_status, StreamStatus::Stale);
+                        break;
                     }
-                    Err(e) => warn!("kraken connect error: {:?}", e),
-                }
-            } else {
-                // fallback: synthetic stream
-                loop {
-                    let mid = 100.0 + (fastrand::f64() - 0.5) * 0.5;
-                    let spread = 0.001;
-                    let bid = (mid * (1.0 - spread / 2.0) * 1e8f64).round() / 1e8f64;
-                    let ask = (mid * (1.0 + spread / 2.0) * 1e8f64).round() / 1e8f64;
-                    let bids = vec![(bid, 1.0), (bid * 0.999, 2.0)];
-                    let asks = vec![(ask, 1.0), (ask * 1.001, 2.0)];
-                    let ob = OrderBook::new(bids, asks);
-                    if let Ok(mut s) = snapshot.lock() { *s = Some(ob.clone()); }
-                    Python::with_gil(|py| {
-                        let cb_ref = cb.bind(py);
-                        if let Ok(py_ob) = Py::new(py, ob.clone()) {
-                            let _ = cb_ref.call1((py_ob,));
-                        }
-                    });
-                    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
                 }
             }
-            }); // end of rt.block_on
-        }); // end of std::thread::spawn
+        }
 
-        Ok(())
+        // Every session-ending path above (Close, ws error, stream end, stale timeout, or a
+        // resync) lands here, so a disconnect always backs off instead of hammering the venue
+        // in a zero-delay reconnect loop.
+        sleep(backoff_delay(attempt)).await;
+        attempt = attempt.saturating_add(1);
     }
+}
 
-    fn latest_snapshot(&self) -> PyResult<Option<OrderBook>> {
-        if let Ok(s) = self.snapshot.lock() { Ok(s.clone()) } else { Ok(None) }
+/// Applies one websocket frame to `book`, emitting an `OrderBook` update/recording it as needed.
+/// Returns `true` if the frame revealed a resync condition (Kraken checksum mismatch or Binance
+/// sequence gap) or the server closed the connection, signalling the caller to drop the session.
+async fn apply_frame(
+    connector: &dyn Connector,
+    symbol: &str,
+    book: &mut local_book::LocalOrderBook,
+    msg: Message,
+    write: &mut (impl SinkExt<Message> + Unpin),
+    snapshot: &Snapshot,
+    cb: &PyObject,
+    record_path: &RecordPath,
+) -> bool {
+    match msg {
+        Message::Text(txt) => match connector.parse_ws(book, &txt) {
+            WsOutcome::Updated(ob) => {
+                if let Ok(mut s) = snapshot.lock() { *s = Some(ob.clone()); }
+                record_tick(record_path, &ob);
+                emit(cb, &ob);
+                false
+            }
+            WsOutcome::Resync => true,
+            WsOutcome::Control(kind) => {
+                info!("{} {}: {} event", connector.name(), symbol, kind);
+                false
+            }
+            WsOutcome::Ignored => false,
+        },
+        Message::Ping(payload) => {
+            let _ = write.send(Message::Pong(payload)).await;
+            false
+        }
+        Message::Pong(_) => false,
+        Message::Close(_) => true,
+        _ => false,
     }
 }
 
-/// Helpers: parse REST & WS payloads
-
-fn parse_binance_rest(v: &Value, key: &str) -> Vec<(f64, f64)> {
-    v.get(key)
-        .and_then(|x| x.as_array())
-        .map(|arr| {
-            arr.iter()
-                .filter_map(|it| {
-                    let p = it.get(0)?.as_str()?.parse::<f64>().ok()?;
-                    let q = it.get(1)?.as_str()?.parse::<f64>().ok()?;
-                    Some((p, q))
-                })
-                .take(5)
-                .collect()
-        })
-        .unwrap_or_default()
+async fn run_synthetic_stream(
+    connector: &dyn Connector,
+    snapshot: &Snapshot,
+    cb: &PyObject,
+    status: &StatusCell,
+    on_status: &StatusCallback,
+    record_path: &RecordPath,
+) {
+    set_status(status, on_status, StreamStatus::Connected);
+    loop {
+        let ob = connector.synthetic_tick();
+        if let Ok(mut s) = snapshot.lock() { *s = Some(ob.clone()); }
+        record_tick(record_path, &ob);
+        emit(cb, &ob);
+        sleep(Duration::from_millis(500)).await;
+    }
 }
 
-fn parse_coinbase_rest(v: &Value, key: &str) -> Vec<(f64, f64)> {
-    v.get(key)
-        .and_then(|x| x.as_array())
-        .map(|arr| {
-            arr.iter()
-                .filter_map(|it| {
-                    let p = it.get(0)?.as_str()?.parse::<f64>().ok()?;
-                    let q = it.get(1)?.as_str()?.parse::<f64>().ok()?;
-                    Some((p, q))
-                })
-                .take(5)
-                .collect()
-        })
-        .unwrap_or_default()
-}
+/// Replays a `ReplayConnector`'s capture for a deterministic backtest: `speed` of `0.0` emits
+/// every frame immediately (as fast as possible), otherwise sleeps `delta_ts / speed` between
+/// frames so the strategy sees the capture's original relative timing. Stops once the file is
+/// exhausted rather than looping, unlike `run_synthetic_stream`.
+async fn run_replay_stream(connector: &dyn Connector, snapshot: &Snapshot, cb: &PyObject, status: &StatusCell, on_status: &StatusCallback) {
+    set_status(status, on_status, StreamStatus::Connected);
+    let path = connector.replay_path();
+    let speed = connector.replay_speed();
+    let contents = match tokio::fs::read_to_string(path).await {
+        Ok(c) => c,
+        Err(e) => {
+            warn!("replay {}: read error: {:?}", path, e);
+            set_status(status, on_status, StreamStatus::Stale);
+            return;
+        }
+    };
 
-fn parse_kraken_rest(v: &Value, key: &str) -> Vec<(f64, f64)> {
-    // Kraken response format: {"result": {"XBTUSDT": {"bids": [...], "asks": [...]}}}
-    v.get("result")
-        .and_then(|result| result.as_object())
-        .and_then(|obj| obj.values().next()) // Get first pair's data
-        .and_then(|pair_data| pair_data.get(key))
-        .and_then(|x| x.as_array())
-        .map(|arr| {
-            arr.iter()
-                .filter_map(|it| {
-                    let p = it.get(0)?.as_str()?.parse::<f64>().ok()?;
-                    let q = it.get(1)?.as_str()?.parse::<f64>().ok()?;
-                    Some((p, q))
-                })
-                .take(5)
-                .collect()
-        })
-        .unwrap_or_default()
+    let mut last_ts: Option<f64> = None;
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let frame: ReplayFrame = match serde_json::from_str(line) {
+            Ok(f) => f,
+            Err(e) => { warn!("replay {}: parse error: {:?}", path, e); continue; }
+        };
+        if speed > 0.0 {
+            if let Some(last) = last_ts {
+                let delta = (frame.ts - last).max(0.0) / speed;
+                if delta > 0.0 {
+                    sleep(Duration::from_secs_f64(delta)).await;
+                }
+            }
+        }
+        last_ts = Some(frame.ts);
+
+        let ob = OrderBook::new(frame.bids, frame.asks);
+        if let Ok(mut s) = snapshot.lock() { *s = Some(ob.clone()); }
+        emit(cb, &ob);
+    }
+    info!("replay {}: capture exhausted, stopping", path);
 }
 
-fn parse_binance_depth_text(txt: &str) -> Result<OrderBook, serde_json::Error> {
-    let v: Value = serde_json::from_str(txt)?;
-    let root = if v.get("e").is_some() || v.get("b").is_some() || v.get("a").is_some() {
-        v
-    } else if let Some(data) = v.get("data") { data.clone() } else { v };
-    let bids = root
-        .get("b")
-        .and_then(|x| x.as_array())
-        .map(|arr| {
-            arr.iter()
-                .filter_map(|it| {
-                    let p = it.get(0)?.as_str()?.parse::<f64>().ok()?;
-                    let q = it.get(1)?.as_str()?.parse::<f64>().ok()?;
-                    Some((p, q))
-                })
-                .take(5)
-                .collect()
-        })
-        .unwrap_or_default();
-    let asks = root
-        .get("a")
-        .and_then(|x| x.as_array())
-        .map(|arr| {
-            arr.iter()
-                .filter_map(|it| {
-                    let p = it.get(0)?.as_str()?.parse::<f64>().ok()?;
-                    let q = it.get(1)?.as_str()?.parse::<f64>().ok()?;
-                    Some((p, q))
-                })
-                .take(5)
-                .collect()
-        })
-        .unwrap_or_default();
-    Ok(OrderBook::new(bids, asks))
+/// Thin PyO3 wrapper around a `Arc<dyn Connector>`; concrete venue behavior lives in `connector`.
+#[pyclass]
+pub struct ExchangeConnector {
+    connector: Arc<dyn Connector>,
+    snapshot: Snapshot,
+    status: StatusCell,
+    on_status: StatusCallback,
+    record_path: RecordPath,
 }
 
-fn parse_coinbase_l2_text(txt: &str) -> Result<OrderBook, serde_json::Error> {
-    let v: Value = serde_json::from_str(txt)?;
-    if let Some(t) = v.get("type").and_then(|x| x.as_str()) {
-        if t == "snapshot" {
-            let bids = parse_coinbase_rest(&v, "bids");
-            let asks = parse_coinbase_rest(&v, "asks");
-            return Ok(OrderBook::new(bids, asks));
-        } else if t == "l2update" {
-            // For simplicity, produce a tiny update (production: apply deltas to maintained book)
-            let bids = vec![(100.0, 1.0)];
-            let asks = vec![(100.2, 1.0)];
-            return Ok(OrderBook::new(bids, asks));
+impl ExchangeConnector {
+    fn from_connector(connector: Arc<dyn Connector>) -> Self {
+        ExchangeConnector {
+            connector,
+            snapshot: Arc::new(Mutex::new(None)),
+            status: Arc::new(Mutex::new(StreamStatus::Reconnecting)),
+            on_status: Arc::new(Mutex::new(None)),
+            record_path: Arc::new(Mutex::new(None)),
         }
     }
-    Ok(OrderBook::new(vec![(100.0, 1.0)], vec![(100.2, 1.0)]))
 }
 
-fn parse_kraken_ws_text(txt: &str) -> Result<OrderBook, serde_json::Error> {
-    let v: Value = serde_json::from_str(txt)?;
-    
-    // Kraken WS messages are arrays: [channelID, data, channelName, pair]
-    if let Some(arr) = v.as_array() {
-        if arr.len() >= 2 {
-            // Check if it's a book update
-            if let Some(data) = arr.get(1).and_then(|d| d.as_object()) {
-                // Snapshot format: {"as": [[price, vol, timestamp]], "bs": [[price, vol, timestamp]]}
-                if data.contains_key("as") && data.contains_key("bs") {
-                    let bids = data.get("bs")
-                        .and_then(|x| x.as_array())
-                        .map(|arr| {
-                            arr.iter()
-                                .filter_map(|it| {
-                                    let p = it.get(0)?.as_str()?.parse::<f64>().ok()?;
-                                    let q = it.get(1)?.as_str()?.parse::<f64>().ok()?;
-                                    Some((p, q))
-                                })
-                                .take(5)
-                                .collect()
-                        })
-                        .unwrap_or_default();
-                    
-                    let asks = data.get("as")
-                        .and_then(|x| x.as_array())
-                        .map(|arr| {
-                            arr.iter()
-                                .filter_map(|it| {
-                                    let p = it.get(0)?.as_str()?.parse::<f64>().ok()?;
-                                    let q = it.get(1)?.as_str()?.parse::<f64>().ok()?;
-                                    Some((p, q))
-                                })
-                                .take(5)
-                                .collect()
-                        })
-                        .unwrap_or_default();
-                    
-                    return Ok(OrderBook::new(bids, asks));
-                }
-            }
+#[pymethods]
+impl ExchangeConnector {
+    #[new]
+    fn new(name: String) -> Self {
+        let _ = env_logger::try_init();
+        Self::from_connector(build_connector(&name))
+    }
+
+    /// Current supervised-stream state: `connected`, `reconnecting`, or `stale`. `reconnecting`
+    /// until `start_stream` has been called and the first connection succeeds.
+    #[getter]
+    fn status(&self) -> String {
+        self.status.lock().unwrap().as_str().to_string()
+    }
+
+    /// Registers a callback invoked with the new status string (`connected`/`reconnecting`/
+    /// `stale`) every time it changes, so a strategy can halt trading during a gap instead of
+    /// polling `status`.
+    fn set_on_status(&mut self, callback: PyObject) -> PyResult<()> {
+        *self.on_status.lock().unwrap() = Some(callback);
+        Ok(())
+    }
+
+    /// Enables (`Some(path)`) or disables (`None`) teeing every streamed `OrderBook` to `path` as
+    /// newline-delimited `{ts, bids, asks}` JSON - the capture format `get_replay_connector`
+    /// reads back for a reproducible backtest.
+    fn set_record_path(&mut self, path: Option<String>) -> PyResult<()> {
+        *self.record_path.lock().unwrap() = path;
+        Ok(())
+    }
+
+    fn list_symbols(&self) -> PyResult<Vec<String>> {
+        Ok(self.connector.list_symbols())
+    }
+
+    /// Blocking snapshot via REST for simplicity
+    fn fetch_orderbook_sync(&self, symbol: String) -> PyResult<OrderBook> {
+        if self.connector.is_synthetic() {
+            return Ok(self.connector.synthetic_snapshot());
         }
+        let url = self.connector.rest_depth_url(&symbol);
+        let resp = reqwest::blocking::get(&url)
+            .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(format!("request: {:?}", e)))?;
+        let v: Value = resp
+            .json()
+            .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(format!("json parse: {:?}", e)))?;
+        Ok(self.connector.parse_rest(&v))
+    }
+
+    /// Start streaming; callback is a Python callable that receives an OrderBook pyobject.
+    /// Spawns a tokio task for async WebSocket handling; the task supervises its own
+    /// reconnection for the lifetime of the connector (see `run_connector_stream`), except for a
+    /// replay connector, which plays its capture through once and stops.
+    fn start_stream(&mut self, _py: Python<'_>, symbol: String, py_callback: PyObject) -> PyResult<()> {
+        let snapshot = self.snapshot.clone();
+        let connector = self.connector.clone();
+        let cb = py_callback.clone();
+        let status = self.status.clone();
+        let on_status = self.on_status.clone();
+        let record_path = self.record_path.clone();
+
+        // Spawn in a new thread with its own tokio runtime
+        std::thread::spawn(move || {
+            let rt = tokio::runtime::Runtime::new().expect("Failed to create tokio runtime");
+            rt.block_on(async move {
+                if connector.is_replay() {
+                    run_replay_stream(connector.as_ref(), &snapshot, &cb, &status, &on_status).await;
+                } else if connector.is_synthetic() {
+                    run_synthetic_stream(connector.as_ref(), &snapshot, &cb, &status, &on_status, &record_path).await;
+                } else {
+                    run_connector_stream(connector.as_ref(), &symbol, &snapshot, &cb, &status, &on_status, &record_path).await;
+                }
+            });
+        });
+
+        Ok(())
+    }
+
+    fn latest_snapshot(&self) -> PyResult<Option<OrderBook>> {
+        if let Ok(s) = self.snapshot.lock() { Ok(s.clone()) } else { Ok(None) }
     }
-    
-    // Return empty orderbook if parsing fails (not a book update message)
-    Ok(OrderBook::new(vec![], vec![]))
 }
 
 /// Uniswap pair reserves reader using ethers subcrates.
@@ -523,6 +571,15 @@ fn get_connector(name: &str) -> PyResult<ExchangeConnector> {
     Ok(ExchangeConnector::new(name.to_string()))
 }
 
+/// Deterministic backtest source: replays a `{ts, bids, asks}`-per-line capture (as written by
+/// `ExchangeConnector.set_record_path`) instead of a live feed. Takes a path rather than a name
+/// because, unlike the other venues, it needs one - see `connector::ReplayConnector`.
+#[pyfunction]
+#[pyo3(signature = (path, speed=1.0))]
+fn get_replay_connector(path: String, speed: f64) -> PyResult<ExchangeConnector> {
+    Ok(ExchangeConnector::from_connector(Arc::new(connector::ReplayConnector::new(path, speed))))
+}
+
 #[pymodule]
 fn rust_connector(m: &Bound<'_, PyModule>) -> PyResult<()> {
     env_logger::init();
@@ -532,5 +589,6 @@ fn rust_connector(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(compute_dex_cex_arbitrage, m)?)?;
     m.add_function(wrap_pyfunction!(list_connectors, m)?)?;
     m.add_function(wrap_pyfunction!(get_connector, m)?)?;
+    m.add_function(wrap_pyfunction!(get_replay_connector, m)?)?;
     Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,324 @@
+//! Venue-maintained local order books: sorted bid/ask maps kept live by applying incremental
+//! updates rather than trusting whatever single frame last arrived, plus the integrity checks
+//! (Kraken's checksum, Binance's `U`/`u` sequence bounds) that tell a caller when the local copy
+//! has drifted and needs a fresh snapshot.
+
+use crate::OrderBook;
+use crc32fast::Hasher;
+use ordered_float::OrderedFloat;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Kraken doesn't expose a pair's `pair_decimals`/`lot_decimals` over the websocket itself (only
+/// via the `AssetPairs` REST endpoint); a fresh book formats checksum tokens with these until
+/// `KrakenConnector::seed` replaces them with the pair's actual precision from
+/// `fetch_kraken_pair_decimals`.
+pub const DEFAULT_PRICE_DECIMALS: u32 = 1;
+pub const DEFAULT_VOLUME_DECIMALS: u32 = 8;
+
+/// Number of top levels Kraken's checksum covers on each side.
+const CHECKSUM_DEPTH: usize = 10;
+
+/// A depth book kept live by applying venue deltas: bids/asks in sorted maps so a level update
+/// is an insert/overwrite (non-zero volume) or a removal (zero volume), never a full re-send.
+#[derive(Debug, Clone)]
+pub struct LocalOrderBook {
+    bids: BTreeMap<OrderedFloat<f64>, f64>,
+    asks: BTreeMap<OrderedFloat<f64>, f64>,
+    price_decimals: u32,
+    volume_decimals: u32,
+    /// Binance diff-depth's last applied `u`; `None` until a REST snapshot has seeded the book.
+    last_update_id: Option<u64>,
+    /// Set whenever a REST snapshot seeds the book; cleared once the first diff since that
+    /// snapshot is applied, so `apply_binance_diff` can relax the gap check for exactly that one.
+    first_event_since_snapshot: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SequenceGap {
+    pub expected: u64,
+    pub got: u64,
+}
+
+impl std::fmt::Display for SequenceGap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "sequence gap: expected first_update_id {}, got {}", self.expected, self.got)
+    }
+}
+
+/// Result of folding one Kraken book message into a [`LocalOrderBook`].
+pub enum KrakenOutcome {
+    /// Snapshot applied, or a delta applied and (if it carried a checksum) verified.
+    Updated,
+    /// A delta's checksum didn't match; the caller should drop the book and resubscribe.
+    ChecksumMismatch,
+    /// Not a book message (e.g. a subscription ack or heartbeat).
+    Ignored,
+}
+
+/// Result of folding one Binance diff-depth frame into a [`LocalOrderBook`].
+pub enum BinanceOutcome {
+    Updated,
+    Gap(SequenceGap),
+    Ignored,
+}
+
+impl LocalOrderBook {
+    pub fn new(price_decimals: u32, volume_decimals: u32) -> Self {
+        LocalOrderBook {
+            bids: BTreeMap::new(),
+            asks: BTreeMap::new(),
+            price_decimals,
+            volume_decimals,
+            last_update_id: None,
+            first_event_since_snapshot: false,
+        }
+    }
+
+    pub fn with_kraken_defaults() -> Self {
+        Self::new(DEFAULT_PRICE_DECIMALS, DEFAULT_VOLUME_DECIMALS)
+    }
+
+    /// Overrides the checksum's price/volume decimal precision - e.g. once `fetch_kraken_pair_decimals`
+    /// has told the caller a pair's actual configured precision, rather than leaving it on the
+    /// generic defaults `with_kraken_defaults` assumed. Must be called before any levels are
+    /// applied: it doesn't rescale volumes already inserted under the old precision.
+    pub fn set_decimals(&mut self, price_decimals: u32, volume_decimals: u32) {
+        self.price_decimals = price_decimals;
+        self.volume_decimals = volume_decimals;
+    }
+
+    /// Drops all local state; the next message must be (or seed from) a fresh snapshot.
+    pub fn clear(&mut self) {
+        self.bids.clear();
+        self.asks.clear();
+        self.last_update_id = None;
+        self.first_event_since_snapshot = false;
+    }
+
+    /// Inserts/overwrites a level, or removes it once its volume drops to zero - the standard
+    /// venue depth-update semantics.
+    pub fn apply_level(&mut self, is_bid: bool, price: f64, volume: f64) {
+        let side = if is_bid { &mut self.bids } else { &mut self.asks };
+        if volume <= 0.0 {
+            side.remove(&OrderedFloat(price));
+        } else {
+            side.insert(OrderedFloat(price), volume);
+        }
+    }
+
+    pub fn apply_levels(&mut self, is_bid: bool, levels: &[(f64, f64)]) {
+        for &(p, q) in levels {
+            self.apply_level(is_bid, p, q);
+        }
+    }
+
+    /// A snapshot replaces each side outright rather than merging into whatever was there.
+    pub fn apply_snapshot(&mut self, bids: &[(f64, f64)], asks: &[(f64, f64)]) {
+        self.bids.clear();
+        self.asks.clear();
+        self.apply_levels(true, bids);
+        self.apply_levels(false, asks);
+    }
+
+    pub fn top_bids(&self, n: usize) -> Vec<(f64, f64)> {
+        self.bids.iter().rev().take(n).map(|(p, q)| (p.0, *q)).collect()
+    }
+
+    pub fn top_asks(&self, n: usize) -> Vec<(f64, f64)> {
+        self.asks.iter().take(n).map(|(p, q)| (p.0, *q)).collect()
+    }
+
+    pub fn to_order_book(&self, depth: usize) -> OrderBook {
+        OrderBook::new(self.top_bids(depth), self.top_asks(depth))
+    }
+
+    /// Formats `value` to `decimals` fractional digits, strips the decimal point, then strips
+    /// leading zeros - Kraken's checksum token spec.
+    fn checksum_token(value: f64, decimals: u32) -> String {
+        let formatted = format!("{:.*}", decimals as usize, value);
+        let digits: String = formatted.chars().filter(|c| *c != '.').collect();
+        let trimmed = digits.trim_start_matches('0');
+        if trimmed.is_empty() {
+            "0".to_string()
+        } else {
+            trimmed.to_string()
+        }
+    }
+
+    /// Kraken's book checksum: CRC32 (IEEE) over the top 10 asks (ascending) then the top 10
+    /// bids (descending), each level contributing its price token then its volume token,
+    /// concatenated into one ASCII buffer.
+    pub fn kraken_checksum(&self) -> u32 {
+        let mut buf = String::new();
+        for (price, qty) in self.top_asks(CHECKSUM_DEPTH) {
+            buf.push_str(&Self::checksum_token(price, self.price_decimals));
+            buf.push_str(&Self::checksum_token(qty, self.volume_decimals));
+        }
+        for (price, qty) in self.top_bids(CHECKSUM_DEPTH) {
+            buf.push_str(&Self::checksum_token(price, self.price_decimals));
+            buf.push_str(&Self::checksum_token(qty, self.volume_decimals));
+        }
+        let mut hasher = Hasher::new();
+        hasher.update(buf.as_bytes());
+        hasher.finalize()
+    }
+
+    /// Compares the locally computed checksum against Kraken's decimal `c` field.
+    pub fn verify_kraken_checksum(&self, expected: &str) -> bool {
+        expected.parse::<u32>().map(|e| e == self.kraken_checksum()).unwrap_or(false)
+    }
+
+    /// Seeds the book from a Binance REST depth snapshot's `lastUpdateId`.
+    pub fn seed_binance_snapshot(&mut self, last_update_id: u64, bids: &[(f64, f64)], asks: &[(f64, f64)]) {
+        self.apply_snapshot(bids, asks);
+        self.last_update_id = Some(last_update_id);
+        self.first_event_since_snapshot = true;
+    }
+
+    /// Applies one Binance diff-depth event's bounds (`U` = first_update_id, `u` =
+    /// last_update_id). Per Binance's documented reconciliation rule, the very first event applied
+    /// after a REST snapshot only needs `U <= lastUpdateId+1 <= u` (its `U` almost always falls
+    /// below the snapshot's `lastUpdateId+1`); every event after that must satisfy the strict
+    /// `first_update_id == last + 1`. Returns the gap (without mutating bids/asks) when the
+    /// relevant bound fails, so the caller can drop the book and resync from a fresh REST snapshot
+    /// instead of serving a silently stale one.
+    pub fn apply_binance_diff(
+        &mut self,
+        first_update_id: u64,
+        last_update_id: u64,
+        bids: &[(f64, f64)],
+        asks: &[(f64, f64)],
+    ) -> Result<(), SequenceGap> {
+        if let Some(last) = self.last_update_id {
+            if last_update_id <= last {
+                return Ok(()); // stale, already covered by the current state
+            }
+            let ok = if self.first_event_since_snapshot {
+                first_update_id <= last + 1 && last_update_id >= last + 1
+            } else {
+                first_update_id == last + 1
+            };
+            if !ok {
+                return Err(SequenceGap { expected: last + 1, got: first_update_id });
+            }
+        }
+        self.apply_levels(true, bids);
+        self.apply_levels(false, asks);
+        self.last_update_id = Some(last_update_id);
+        self.first_event_since_snapshot = false;
+        Ok(())
+    }
+}
+
+fn parse_levels(v: Option<&Value>) -> Vec<(f64, f64)> {
+    v.and_then(|x| x.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|it| {
+                    let p = it.get(0)?.as_str()?.parse::<f64>().ok()?;
+                    let q = it.get(1)?.as_str()?.parse::<f64>().ok()?;
+                    Some((p, q))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Folds one Coinbase `level2` frame (`snapshot` or `l2update`) into `book`. Unrecognized frame
+/// types (subscription acks, heartbeats) are left unapplied.
+pub fn apply_coinbase_message(book: &mut LocalOrderBook, v: &Value) -> bool {
+    match v.get("type").and_then(|t| t.as_str()) {
+        Some("snapshot") => {
+            book.apply_snapshot(&parse_levels(v.get("bids")), &parse_levels(v.get("asks")));
+            true
+        }
+        Some("l2update") => {
+            let Some(changes) = v.get("changes").and_then(|c| c.as_array()) else { return false };
+            for change in changes {
+                let side = change.get(0).and_then(|s| s.as_str());
+                let price = change.get(1).and_then(|s| s.as_str()).and_then(|s| s.parse::<f64>().ok());
+                let qty = change.get(2).and_then(|s| s.as_str()).and_then(|s| s.parse::<f64>().ok());
+                if let (Some(side), Some(price), Some(qty)) = (side, price, qty) {
+                    book.apply_level(side == "buy", price, qty);
+                }
+            }
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Folds one Kraken book message (an array `[channelID, data, channelName, pair]`) into `book`.
+/// A snapshot carries `as`/`bs`; a delta carries `a` and/or `b` plus (when present) the `c`
+/// checksum field, verified against the book's own checksum computation.
+pub fn apply_kraken_message(book: &mut LocalOrderBook, v: &Value) -> KrakenOutcome {
+    let Some(arr) = v.as_array() else { return KrakenOutcome::Ignored };
+    let Some(data) = arr.get(1).and_then(|d| d.as_object()) else { return KrakenOutcome::Ignored };
+
+    if data.contains_key("as") || data.contains_key("bs") {
+        book.apply_snapshot(&parse_levels(data.get("bs")), &parse_levels(data.get("as")));
+        return KrakenOutcome::Updated;
+    }
+
+    if data.contains_key("a") || data.contains_key("b") {
+        if let Some(a) = data.get("a") {
+            book.apply_levels(false, &parse_levels(Some(a)));
+        }
+        if let Some(b) = data.get("b") {
+            book.apply_levels(true, &parse_levels(Some(b)));
+        }
+        if let Some(checksum) = data.get("c").and_then(|c| c.as_str()) {
+            if !book.verify_kraken_checksum(checksum) {
+                return KrakenOutcome::ChecksumMismatch;
+            }
+        }
+        return KrakenOutcome::Updated;
+    }
+
+    KrakenOutcome::Ignored
+}
+
+/// Folds one Binance `depthUpdate` frame into `book` (see `apply_binance_diff` for the gap
+/// semantics). Frames without `U`/`u` (e.g. a combined-stream wrapper's non-depth events) are
+/// ignored rather than erroring.
+pub fn apply_binance_message(book: &mut LocalOrderBook, v: &Value) -> BinanceOutcome {
+    let root = v.get("data").unwrap_or(v);
+    let (Some(first), Some(last)) = (root.get("U").and_then(|x| x.as_u64()), root.get("u").and_then(|x| x.as_u64()))
+    else {
+        return BinanceOutcome::Ignored;
+    };
+    let bids = parse_levels(root.get("b"));
+    let asks = parse_levels(root.get("a"));
+    match book.apply_binance_diff(first, last, &bids, &asks) {
+        Ok(()) => BinanceOutcome::Updated,
+        Err(gap) => BinanceOutcome::Gap(gap),
+    }
+}
+
+/// Fetches a Binance REST depth snapshot (`lastUpdateId` + full bids/asks) to (re)seed a local
+/// book before applying the diff-depth stream, per Binance's documented local-book recipe.
+pub async fn fetch_binance_snapshot(symbol: &str) -> Result<(u64, Vec<(f64, f64)>, Vec<(f64, f64)>), String> {
+    let url = format!("https://api.binance.com/api/v3/depth?symbol={}&limit=1000", symbol.to_uppercase());
+    let resp = reqwest::get(&url).await.map_err(|e| e.to_string())?;
+    let v: Value = resp.json().await.map_err(|e| e.to_string())?;
+    let last_update_id = v.get("lastUpdateId").and_then(|x| x.as_u64()).ok_or_else(|| "missing lastUpdateId".to_string())?;
+    Ok((last_update_id, parse_levels(v.get("bids")), parse_levels(v.get("asks"))))
+}
+
+/// Fetches `pair_decimals`/`lot_decimals` for `symbol` from Kraken's `AssetPairs` endpoint, so
+/// `kraken_checksum` can be computed at the pair's actual configured precision instead of always
+/// assuming the common USD-pair defaults (wrong for e.g. ETH/USD's 2-decimal price).
+pub async fn fetch_kraken_pair_decimals(symbol: &str) -> Result<(u32, u32), String> {
+    let url = format!("https://api.kraken.com/0/public/AssetPairs?pair={}", symbol);
+    let resp = reqwest::get(&url).await.map_err(|e| e.to_string())?;
+    let v: Value = resp.json().await.map_err(|e| e.to_string())?;
+    let pair = v
+        .get("result")
+        .and_then(|r| r.as_object())
+        .and_then(|obj| obj.values().next())
+        .ok_or_else(|| format!("no AssetPairs result for {}", symbol))?;
+    let price_decimals = pair.get("pair_decimals").and_then(|x| x.as_u64()).ok_or_else(|| "missing pair_decimals".to_string())?;
+    let volume_decimals = pair.get("lot_decimals").and_then(|x| x.as_u64()).ok_or_else(|| "missing lot_decimals".to_string())?;
+    Ok((price_decimals as u32, volume_decimals as u32))
+}
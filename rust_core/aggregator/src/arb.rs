@@ -0,0 +1,291 @@
+use connectors_common::types::{MarketTick, OrderBookSnapshot};
+use log::{info, warn};
+use std::collections::{HashMap, HashSet};
+use tokio::sync::broadcast;
+use tokio::time::Duration;
+
+/// Tolerance below which a relaxation is treated as floating-point noise rather than a real
+/// negative cycle.
+const EPS: f64 = 1e-9;
+
+/// Fee/slippage applied when a venue has no entry in [`ArbDetector::with_fee_bps`].
+const DEFAULT_FEE_BPS: f64 = 10.0; // 0.10%
+
+/// One directed leg of a detected cycle: trade `from` into `to` on `exchange`'s `pair` book.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArbLeg {
+    pub exchange: String,
+    pub pair: String,
+    pub from: String,
+    pub to: String,
+}
+
+/// A profitable trade cycle found by [`ArbDetector::scan`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Opportunity {
+    /// Legs in trade order; `legs[i].to == legs[i + 1].from`, and the last leg's `to` closes
+    /// back to the first leg's `from`.
+    pub legs: Vec<ArbLeg>,
+    /// `-sum(leg weights)`; positive means the cycle compounds to more than it started with.
+    pub log_return: f64,
+    /// The smallest per-leg size implied by the latest known [`OrderBookSnapshot`] depth, in the
+    /// units of that leg's `from` asset. `f64::INFINITY` if no leg has a known book yet, meaning
+    /// the cycle is unsized rather than unlimited.
+    pub limiting_size: f64,
+}
+
+#[derive(Debug, Clone)]
+struct Edge {
+    exchange: String,
+    pair: String,
+    weight: f64,
+}
+
+/// Binance quote assets this lab trades against, longest first so e.g. `"BTCUSDT"` matches
+/// `"USDT"` rather than stopping early on some shorter asset that happens to be a suffix of it.
+const BINANCE_QUOTE_ASSETS: &[&str] = &["USDT", "BUSD", "USDC", "TUSD", "USD", "EUR", "GBP", "BTC", "ETH"];
+
+/// Splits a venue pair string into `(base, quote)`. Venues spell pairs differently (Kraken
+/// `"XBT/USD"`, Coinbase `"BTC-USD"`, Binance `"BTCUSDT"` with no separator at all); the
+/// separated forms split on their delimiter directly, while an unseparated Binance-style pair is
+/// matched against [`BINANCE_QUOTE_ASSETS`]'s known suffixes. A pair matching neither is skipped
+/// rather than guessed at.
+fn split_pair(pair: &str) -> Option<(String, String)> {
+    if let Some((base, quote)) = pair.split_once('/').or_else(|| pair.split_once('-')) {
+        return Some((base.to_string(), quote.to_string()));
+    }
+    BINANCE_QUOTE_ASSETS
+        .iter()
+        .find(|q| pair.len() > q.len() && pair.ends_with(**q))
+        .map(|q| (pair[..pair.len() - q.len()].to_string(), q.to_string()))
+}
+
+/// Detects triangular and cross-exchange arbitrage over the live quotes an [`Aggregator`] (or a
+/// replay of one) publishes. Each asset is a graph node; each tick contributes two directed
+/// edges weighted `-ln(effective_rate)` (buy `quote -> base` at the ask, sell `base -> quote` at
+/// the bid, both after fee/slippage), so a closed cycle is profitable iff its edge weights sum
+/// negative. Multiple venues quoting the same pair collapse to a single best-rate edge per
+/// `(from, to)`, which is also how the same asset trading on two venues falls out as a 2-cycle:
+/// the cheapest ask on one venue and the richest bid on another.
+///
+/// [`Aggregator`]: crate::Aggregator
+pub struct ArbDetector {
+    fee_bps: HashMap<String, f64>,
+    edges: HashMap<(String, String), Edge>,
+    depth: HashMap<(String, String), OrderBookSnapshot>,
+    dirty: bool,
+    out_tx: broadcast::Sender<Opportunity>,
+}
+
+impl ArbDetector {
+    pub fn new(buffer: usize) -> Self {
+        let (out_tx, _) = broadcast::channel(buffer);
+        ArbDetector { fee_bps: HashMap::new(), edges: HashMap::new(), depth: HashMap::new(), dirty: false, out_tx }
+    }
+
+    /// Overrides the fee/slippage (in basis points) charged on `exchange`'s fills.
+    pub fn with_fee_bps(mut self, exchange: impl Into<String>, bps: f64) -> Self {
+        self.fee_bps.insert(exchange.into(), bps);
+        self
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<Opportunity> {
+        self.out_tx.subscribe()
+    }
+
+    fn fee_fraction(&self, exchange: &str) -> f64 {
+        self.fee_bps.get(exchange).copied().unwrap_or(DEFAULT_FEE_BPS) / 10_000.0
+    }
+
+    /// Folds one tick's bid/ask into the buy and sell edges for its pair, keeping only the
+    /// better-rate (lower-weight) edge per directed `(from, to)` when another venue already
+    /// quotes the same pair.
+    pub fn ingest_tick(&mut self, tick: &MarketTick) {
+        let Some((base, quote)) = split_pair(&tick.pair) else {
+            warn!("arb: pair {} has no base/quote separator, skipping", tick.pair);
+            return;
+        };
+        if tick.bid <= 0.0 || tick.ask <= 0.0 {
+            return;
+        }
+        let fee = self.fee_fraction(&tick.exchange);
+
+        let buy_rate = (1.0 / tick.ask) * (1.0 - fee);
+        self.upsert_edge(quote.clone(), base.clone(), tick.exchange.clone(), tick.pair.clone(), -buy_rate.ln());
+
+        let sell_rate = tick.bid * (1.0 - fee);
+        self.upsert_edge(base, quote, tick.exchange.clone(), tick.pair.clone(), -sell_rate.ln());
+
+        self.dirty = true;
+    }
+
+    fn upsert_edge(&mut self, from: String, to: String, exchange: String, pair: String, weight: f64) {
+        let key = (from, to);
+        let keep_existing = matches!(self.edges.get(&key), Some(e) if e.weight <= weight);
+        if !keep_existing {
+            self.edges.insert(key, Edge { exchange, pair, weight });
+        }
+    }
+
+    /// Records the latest L2 depth for a book, used only to size opportunities that trade
+    /// through it; wire this to an [`L2Maintainer`](crate::l2::L2Maintainer)'s snapshot stream
+    /// alongside the tick feed.
+    pub fn ingest_depth(&mut self, snapshot: OrderBookSnapshot) {
+        self.depth.insert((snapshot.exchange.clone(), snapshot.pair.clone()), snapshot);
+    }
+
+    /// Runs Bellman-Ford over the current best-edge graph (V-1 relaxation passes, multi-source
+    /// from every node at distance 0 so a negative cycle anywhere is reachable) and returns every
+    /// distinct negative cycle flagged by one more relaxation pass.
+    pub fn scan(&self) -> Vec<Opportunity> {
+        let mut nodes: Vec<String> = Vec::new();
+        let mut seen_nodes = HashSet::new();
+        for (from, to) in self.edges.keys() {
+            for n in [from, to] {
+                if seen_nodes.insert(n.clone()) {
+                    nodes.push(n.clone());
+                }
+            }
+        }
+        if nodes.is_empty() {
+            return Vec::new();
+        }
+        let idx: HashMap<&str, usize> = nodes.iter().enumerate().map(|(i, s)| (s.as_str(), i)).collect();
+        let edges: Vec<(usize, usize, &Edge)> =
+            self.edges.iter().map(|((f, t), e)| (idx[f.as_str()], idx[t.as_str()], e)).collect();
+
+        let n = nodes.len();
+        let mut dist = vec![0.0f64; n];
+        let mut pred: Vec<Option<usize>> = vec![None; n];
+        for _ in 0..n.saturating_sub(1) {
+            let mut updated = false;
+            for &(u, v, e) in &edges {
+                if dist[u] + e.weight < dist[v] - EPS {
+                    dist[v] = dist[u] + e.weight;
+                    pred[v] = Some(u);
+                    updated = true;
+                }
+            }
+            if !updated {
+                break;
+            }
+        }
+
+        let mut flagged = HashSet::new();
+        for &(u, v, e) in &edges {
+            if dist[u] + e.weight < dist[v] - EPS {
+                pred[v] = Some(u);
+                flagged.insert(v);
+            }
+        }
+
+        let mut opportunities = Vec::new();
+        let mut emitted: Vec<HashSet<usize>> = Vec::new();
+        for v in flagged {
+            let Some(cycle) = Self::reconstruct_cycle(&pred, v) else { continue };
+            let members: HashSet<usize> = cycle.iter().copied().collect();
+            if emitted.iter().any(|e| *e == members) {
+                continue;
+            }
+            if let Some(opp) = self.build_opportunity(&cycle, &nodes) {
+                emitted.push(members);
+                opportunities.push(opp);
+            }
+        }
+        opportunities
+    }
+
+    /// Walks predecessor pointers from `start`, rotating until a node repeats, and returns the
+    /// repeated segment (the cycle itself, in the direction the pointers were walked).
+    fn reconstruct_cycle(pred: &[Option<usize>], start: usize) -> Option<Vec<usize>> {
+        let mut visited = Vec::new();
+        let mut index_of = HashMap::new();
+        let mut v = start;
+        loop {
+            if let Some(&i) = index_of.get(&v) {
+                return Some(visited[i..].to_vec());
+            }
+            if visited.len() > pred.len() {
+                return None; // malformed predecessor chain; bail rather than loop forever
+            }
+            index_of.insert(v, visited.len());
+            visited.push(v);
+            v = pred[v]?;
+        }
+    }
+
+    /// Turns a cycle of node indices (walked backwards via predecessor pointers) into an
+    /// `Opportunity`, looking up each consecutive pair's edge for its venue/pair and weight.
+    fn build_opportunity(&self, cycle: &[usize], nodes: &[String]) -> Option<Opportunity> {
+        let n = cycle.len();
+        if n < 2 {
+            return None;
+        }
+        let mut pairs: Vec<(usize, usize)> = (1..n).rev().map(|m| (cycle[m], cycle[m - 1])).collect();
+        pairs.push((cycle[0], cycle[n - 1]));
+
+        let mut legs = Vec::with_capacity(pairs.len());
+        let mut weight_sum = 0.0;
+        let mut limiting_size = f64::INFINITY;
+        for (from_idx, to_idx) in pairs {
+            let from = nodes[from_idx].clone();
+            let to = nodes[to_idx].clone();
+            let edge = self.edges.get(&(from.clone(), to.clone()))?;
+            weight_sum += edge.weight;
+            if let Some(size) = self.leg_depth_size(&edge.exchange, &edge.pair, &from) {
+                limiting_size = limiting_size.min(size);
+            }
+            legs.push(ArbLeg { exchange: edge.exchange.clone(), pair: edge.pair.clone(), from, to });
+        }
+
+        Some(Opportunity { legs, log_return: -weight_sum, limiting_size })
+    }
+
+    /// Best-level depth available to trade `from` into this leg's other asset, in `from` units:
+    /// the bid's base quantity when selling the base asset, or the ask's base quantity converted
+    /// to quote when buying it. `None` if no snapshot has been seen for this book yet.
+    fn leg_depth_size(&self, exchange: &str, pair: &str, from: &str) -> Option<f64> {
+        let snapshot = self.depth.get(&(exchange.to_string(), pair.to_string()))?;
+        let (base, _quote) = split_pair(pair)?;
+        if from == base {
+            snapshot.bids.first().map(|l| l.qty)
+        } else {
+            snapshot.asks.first().map(|l| l.qty * l.price)
+        }
+    }
+
+    /// Drives the detector off an `Aggregator::subscribe()` stream, re-scanning at most once per
+    /// `debounce` window so a burst of ticks triggers one Bellman-Ford pass instead of one per
+    /// tick.
+    pub async fn run(mut self, mut ticks: broadcast::Receiver<MarketTick>, debounce: Duration) {
+        loop {
+            match ticks.recv().await {
+                Ok(tick) => self.ingest_tick(&tick),
+                Err(broadcast::error::RecvError::Lagged(n)) => {
+                    info!("arb detector lagged, dropped {} ticks", n);
+                }
+                Err(broadcast::error::RecvError::Closed) => return,
+            }
+
+            let deadline = tokio::time::sleep(debounce);
+            tokio::pin!(deadline);
+            loop {
+                tokio::select! {
+                    _ = &mut deadline => break,
+                    res = ticks.recv() => match res {
+                        Ok(tick) => self.ingest_tick(&tick),
+                        Err(broadcast::error::RecvError::Lagged(n)) => info!("arb detector lagged, dropped {} ticks", n),
+                        Err(broadcast::error::RecvError::Closed) => return,
+                    },
+                }
+            }
+
+            if self.dirty {
+                self.dirty = false;
+                for opp in self.scan() {
+                    let _ = self.out_tx.send(opp);
+                }
+            }
+        }
+    }
+}
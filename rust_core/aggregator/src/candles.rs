@@ -0,0 +1,131 @@
+use connectors_common::types::MarketTick;
+use log::info;
+use std::collections::HashMap;
+use tokio::sync::broadcast;
+
+/// Interval presets, in milliseconds, suitable for `CandleAggregator::new`.
+pub const INTERVAL_1S: u64 = 1_000;
+pub const INTERVAL_1M: u64 = 60_000;
+pub const INTERVAL_5M: u64 = 300_000;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candle {
+    pub exchange: String,
+    pub pair: String,
+    pub interval_ms: u64,
+    pub bucket_start: u128,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: u64, // tick count proxy
+}
+
+impl Candle {
+    fn opening(tick: &MarketTick, interval_ms: u64, bucket_start: u128, mid: f64) -> Self {
+        Candle {
+            exchange: tick.exchange.clone(),
+            pair: tick.pair.clone(),
+            interval_ms,
+            bucket_start,
+            open: mid,
+            high: mid,
+            low: mid,
+            close: mid,
+            volume: 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+struct BarKey {
+    exchange: String,
+    pair: String,
+    interval_ms: u64,
+}
+
+/// Rolls an `Aggregator` tick stream into OHLCV bars keyed by `(exchange, pair, interval)` for
+/// a configurable set of intervals. Bars are bucketed by *event time* (`MarketTick.ts`), not
+/// ingest time, so a tick lands in the bar its timestamp belongs to rather than whichever bar
+/// happens to be open when it arrives, as long as that bar is still open. A tick whose bucket is
+/// older than the open bar for its key is dropped; already-closed bars are not amended.
+pub struct CandleAggregator {
+    intervals: Vec<u64>,
+    open_bars: HashMap<BarKey, Candle>,
+    out_tx: broadcast::Sender<Candle>,
+}
+
+impl CandleAggregator {
+    pub fn new(intervals: Vec<u64>, buffer: usize) -> Self {
+        let (out_tx, _) = broadcast::channel(buffer);
+        CandleAggregator { intervals, open_bars: HashMap::new(), out_tx }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<Candle> {
+        self.out_tx.subscribe()
+    }
+
+    /// Feeds one tick into every configured interval's bar for `(exchange, pair)`, updating the
+    /// open bar's high/low/close/volume, or rolling it over (returning the now-closed bar) when
+    /// the tick's event time has moved past the bar's bucket.
+    fn apply_tick(&mut self, tick: &MarketTick) -> Vec<Candle> {
+        let mid = (tick.bid + tick.ask) / 2.0;
+        let mut closed = Vec::new();
+        for &interval_ms in &self.intervals {
+            let bucket_start = (tick.ts / interval_ms as u128) * interval_ms as u128;
+            let key = BarKey { exchange: tick.exchange.clone(), pair: tick.pair.clone(), interval_ms };
+            match self.open_bars.get_mut(&key) {
+                Some(bar) if bar.bucket_start == bucket_start => {
+                    bar.high = bar.high.max(mid);
+                    bar.low = bar.low.min(mid);
+                    bar.close = mid;
+                    bar.volume += 1;
+                }
+                Some(bar) if bucket_start > bar.bucket_start => {
+                    closed.push(bar.clone());
+                    self.open_bars.insert(key, Candle::opening(tick, interval_ms, bucket_start, mid));
+                }
+                Some(_) => {
+                    // A late/out-of-order tick for a bucket that's already behind the open bar:
+                    // its own bucket already closed, so there's nothing still open to fold it
+                    // into. Drop it rather than clobbering the current, still-accumulating bar.
+                }
+                None => {
+                    self.open_bars.insert(key, Candle::opening(tick, interval_ms, bucket_start, mid));
+                }
+            }
+        }
+        closed
+    }
+
+    /// Ingests a single live tick, emitting any bar it closes on the candle broadcast channel.
+    pub fn ingest(&mut self, tick: &MarketTick) {
+        for bar in self.apply_tick(tick) {
+            let _ = self.out_tx.send(bar);
+        }
+    }
+
+    /// Drives the aggregator off an `Aggregator::subscribe()` stream until the channel closes.
+    pub async fn run(mut self, mut ticks: broadcast::Receiver<MarketTick>) {
+        loop {
+            match ticks.recv().await {
+                Ok(tick) => self.ingest(&tick),
+                Err(broadcast::error::RecvError::Lagged(n)) => {
+                    info!("candle aggregator lagged, dropped {} ticks", n);
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    }
+
+    /// Reconstructs closed bars from historical ticks (pulled out-of-band via the REST
+    /// connectors) ahead of live streaming, restricted to `[start_ts, end_ts)`. Ticks are
+    /// sorted by event time before bucketing so an out-of-order backfill source still produces
+    /// correctly ordered bars. Any bar still open at `end_ts` is kept in `open_bars` so a
+    /// subsequent `ingest`/`run` continues the same bar rather than starting fresh.
+    pub fn backfill(&mut self, ticks: &[MarketTick], start_ts: u128, end_ts: u128) -> Vec<Candle> {
+        let mut ordered: Vec<&MarketTick> = ticks.iter().filter(|t| t.ts >= start_ts && t.ts < end_ts).collect();
+        ordered.sort_by_key(|t| t.ts);
+        ordered.into_iter().flat_map(|t| self.apply_tick(t)).collect()
+    }
+}
@@ -0,0 +1,140 @@
+use connectors_common::types::{DepthDiff, DepthSnapshot, OrderBookLevel, OrderBookSnapshot};
+use log::warn;
+use rust_core::orderbook::{Decimal, Order, OrderBook};
+use std::collections::{HashMap, VecDeque};
+use tokio::sync::broadcast;
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+struct BookKey {
+    exchange: String,
+    pair: String,
+}
+
+enum BookState {
+    /// Waiting for a REST snapshot; diffs arriving in the meantime are buffered.
+    AwaitingSnapshot { buffered: Vec<DepthDiff> },
+    /// Live and tracking the last applied update id for gap detection. `first_since_snapshot` is
+    /// set when a REST snapshot seeds the book and cleared once its first diff is applied, so
+    /// that one diff's gap check can be relaxed per Binance's documented reconciliation rule.
+    Live { book: OrderBook, last_applied: u64, first_since_snapshot: bool },
+}
+
+/// Maintains a live [`OrderBook`] per `(exchange, pair)` from venue diff-depth streams, closing
+/// the loop to `OrderBook::apply_snapshot`/`apply_delta` that venue connectors otherwise never
+/// feed (they only ever surface top-of-book `MarketTick`s). A book starts in
+/// `AwaitingSnapshot`; once `apply_snapshot` seeds it, `apply_diff` tracks the venue's sequence
+/// numbers and discards local state back to `AwaitingSnapshot` the moment a gap is detected, so
+/// a stale book is never silently served.
+pub struct L2Maintainer {
+    books: HashMap<BookKey, BookState>,
+    out_tx: broadcast::Sender<OrderBookSnapshot>,
+}
+
+impl L2Maintainer {
+    pub fn new(buffer: usize) -> Self {
+        let (out_tx, _) = broadcast::channel(buffer);
+        L2Maintainer { books: HashMap::new(), out_tx }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<OrderBookSnapshot> {
+        self.out_tx.subscribe()
+    }
+
+    /// True if `(exchange, pair)` has no live book and needs a REST snapshot before its diff
+    /// stream can be applied (freshly started, or a gap discarded the previous state).
+    pub fn needs_resync(&self, exchange: &str, pair: &str) -> bool {
+        let key = BookKey { exchange: exchange.to_string(), pair: pair.to_string() };
+        !matches!(self.books.get(&key), Some(BookState::Live { .. }))
+    }
+
+    /// Seeds (or reseeds) a book from a REST snapshot, then replays any diffs that were buffered
+    /// while the snapshot was in flight whose range ends after the snapshot's sequence (including
+    /// one that straddles it); diffs entirely behind the snapshot are stale and dropped.
+    pub fn apply_snapshot(&mut self, snapshot: DepthSnapshot) {
+        let key = BookKey { exchange: snapshot.exchange.clone(), pair: snapshot.pair.clone() };
+        let mut book = OrderBook::new();
+        book.apply_snapshot(&snapshot.bids, &snapshot.asks, snapshot.ts);
+
+        let buffered = match self.books.remove(&key) {
+            Some(BookState::AwaitingSnapshot { buffered }) => buffered,
+            _ => Vec::new(),
+        };
+        self.books.insert(
+            key.clone(),
+            BookState::Live { book, last_applied: snapshot.last_update_id, first_since_snapshot: true },
+        );
+
+        let mut pending: Vec<DepthDiff> = buffered.into_iter().filter(|d| d.last_update_id > snapshot.last_update_id).collect();
+        pending.sort_by_key(|d| d.first_update_id);
+        for diff in pending {
+            self.apply_diff(diff);
+        }
+
+        if let Some(BookState::Live { book, .. }) = self.books.get(&key) {
+            let _ = self.out_tx.send(to_snapshot(&key, book));
+        }
+    }
+
+    /// Applies one venue diff. Returns `true` if this diff revealed a sequence gap (the caller
+    /// should fetch a fresh REST snapshot and call `apply_snapshot`); the book is left in
+    /// `AwaitingSnapshot` in that case, buffering this diff so it isn't lost once resynced.
+    pub fn apply_diff(&mut self, diff: DepthDiff) -> bool {
+        let key = BookKey { exchange: diff.exchange.clone(), pair: diff.pair.clone() };
+
+        let gap = match self.books.get(&key) {
+            Some(BookState::Live { last_applied, first_since_snapshot, .. }) => {
+                if diff.last_update_id <= *last_applied {
+                    return false; // stale, already covered by the current state
+                }
+                if *first_since_snapshot {
+                    !(diff.first_update_id <= *last_applied + 1 && diff.last_update_id >= *last_applied + 1)
+                } else {
+                    diff.first_update_id != *last_applied + 1
+                }
+            }
+            _ => false, // not live yet; falls through to buffering below
+        };
+
+        if gap {
+            warn!("sequence gap on {}/{} (expected {:?}, got first={}), discarding book and resyncing",
+                diff.exchange, diff.pair, self.last_applied(&key), diff.first_update_id);
+            self.books.insert(key, BookState::AwaitingSnapshot { buffered: vec![diff] });
+            return true;
+        }
+
+        match self.books.entry(key.clone()).or_insert_with(|| BookState::AwaitingSnapshot { buffered: Vec::new() }) {
+            BookState::AwaitingSnapshot { buffered } => {
+                buffered.push(diff);
+                false
+            }
+            BookState::Live { book, last_applied, first_since_snapshot } => {
+                book.apply_delta(&diff.bids, &diff.asks, diff.ts);
+                *last_applied = diff.last_update_id;
+                *first_since_snapshot = false;
+                let _ = self.out_tx.send(to_snapshot(&key, book));
+                false
+            }
+        }
+    }
+
+    fn last_applied(&self, key: &BookKey) -> Option<u64> {
+        match self.books.get(key) {
+            Some(BookState::Live { last_applied, .. }) => Some(*last_applied),
+            _ => None,
+        }
+    }
+}
+
+fn level_qty(queue: &VecDeque<Order>) -> f64 {
+    queue.iter().fold(Decimal::ZERO, |acc, o| acc.saturating_add(o.qty)).to_f64()
+}
+
+fn to_snapshot(key: &BookKey, book: &OrderBook) -> OrderBookSnapshot {
+    OrderBookSnapshot {
+        exchange: key.exchange.clone(),
+        pair: key.pair.clone(),
+        bids: book.bids.levels.iter().rev().map(|(p, q)| OrderBookLevel { price: p.to_f64(), qty: level_qty(q) }).collect(),
+        asks: book.asks.levels.iter().map(|(p, q)| OrderBookLevel { price: p.to_f64(), qty: level_qty(q) }).collect(),
+        ts: book.ts as u128,
+    }
+}
@@ -2,6 +2,10 @@ use connectors_common::types::MarketTick;
 use tokio::sync::{mpsc, broadcast};
 use log::info;
 
+pub mod arb;
+pub mod candles;
+pub mod l2;
+
 pub struct Aggregator {
     tx: broadcast::Sender<MarketTick>,
 }
@@ -1,61 +1,72 @@
+use async_trait::async_trait;
+use connectors_common::connector::Connector;
+use connectors_common::errors::ConnectorError;
+use connectors_common::reconnect::{ConnectionState, ReconnectingFeed};
 use connectors_common::types::MarketTick;
-use futures::{SinkExt, StreamExt};
-use tokio::sync::mpsc::Sender;
-use tokio_tungstenite::connect_async;
-use tungstenite::Message;
 use serde_json::Value;
-use log::{info, warn};
-
-pub async fn run_binance_ws(mut tx: Sender<MarketTick>) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let url = "wss://stream.binance.com:9443/ws/!miniTicker@arr";
-    info!("Connecting to {}", url);
-    let (ws_stream, _) = connect_async(url).await?;
-    let (_, mut read) = ws_stream.split();
-    while let Some(msg) = read.next().await {
-        match msg {
-            Ok(Message::Text(txt)) => {
-                if let Ok(v) = serde_json::from_str::<Value>(&txt) {
-                    if let Some(arr) = v.as_array() {
-                        for item in arr {
-                            let s = item.get("s").and_then(|v| v.as_str()).unwrap_or_default().to_string();
-                            let bid = item.get("b").and_then(|v| v.as_str()).and_then(|s| s.parse::<f64>().ok()).unwrap_or(0.0);
-                            let ask = item.get("a").and_then(|v| v.as_str()).and_then(|s| s.parse::<f64>().ok()).unwrap_or(0.0);
-                            let tick = MarketTick {
-                                exchange: "binance".to_string(),
-                                pair: s,
-                                bid,
-                                ask,
-                                ts: chrono::Utc::now().timestamp_millis() as u128,
-                            };
-                            let _ = tx.send(tick).await;
-                        }
-                    } else {
-                        if let (Some(symbol), Some(b), Some(a)) = (
-                            v.get("s").and_then(|v| v.as_str()),
-                            v.get("b").and_then(|v| v.as_str()),
-                            v.get("a").and_then(|v| v.as_str()),
-                        ) {
-                            if let (Ok(bf), Ok(af)) = (b.parse::<f64>(), a.parse::<f64>()) {
-                                let tick = MarketTick {
-                                    exchange: "binance".to_string(),
-                                    pair: symbol.to_string(),
-                                    bid: bf,
-                                    ask: af,
-                                    ts: chrono::Utc::now().timestamp_millis() as u128,
-                                };
-                                let _ = tx.send(tick).await;
-                            }
-                        }
-                    }
-                } else {
-                    warn!("failed to parse binance ws message");
-                }
-            }
-            Ok(_) => {}
-            Err(e) => {
-                warn!("ws error: {:?}", e);
-            }
+use std::time::Duration;
+use tokio::sync::mpsc::Sender;
+use tokio::sync::{oneshot, watch};
+
+const URL: &str = "wss://stream.binance.com:9443/ws/!miniTicker@arr";
+const HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(15);
+
+fn subscribe_msg() -> Option<String> {
+    None // the !miniTicker@arr stream is subscribed implicitly by the URL itself
+}
+
+fn parse_tick(text: &str) -> Vec<MarketTick> {
+    let v: Value = match serde_json::from_str(text) {
+        Ok(v) => v,
+        Err(_) => return Vec::new(),
+    };
+    let items: Vec<&Value> = match v.as_array() {
+        Some(arr) => arr.iter().collect(),
+        None => vec![&v],
+    };
+    items
+        .into_iter()
+        .filter_map(|item| {
+            let pair = item.get("s").and_then(|v| v.as_str())?.to_string();
+            let bid = item.get("b").and_then(|v| v.as_str()).and_then(|s| s.parse::<f64>().ok())?;
+            let ask = item.get("a").and_then(|v| v.as_str()).and_then(|s| s.parse::<f64>().ok())?;
+            Some(MarketTick { exchange: "binance".to_string(), pair, bid, ask, ts: chrono::Utc::now().timestamp_millis() as u128 })
+        })
+        .collect()
+}
+
+/// Runs the Binance mini-ticker feed under a `ReconnectingFeed`: survives disconnects and parse
+/// errors with jittered backoff and force-reconnects on staleness. Never returns under normal
+/// operation (only if `tx` is dropped); `state_tx` exposes connection transitions to callers
+/// that need to gate trading while the feed is down.
+pub async fn run_binance_ws_resilient(tx: Sender<MarketTick>, state_tx: watch::Sender<ConnectionState>) {
+    let feed = ReconnectingFeed::new(URL, HEARTBEAT_TIMEOUT);
+    feed.run(subscribe_msg, parse_tick, tx, state_tx).await;
+}
+
+/// Convenience wrapper for callers that don't need connection-state events.
+pub async fn run_binance_ws(tx: Sender<MarketTick>) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let (state_tx, _state_rx) = watch::channel(ConnectionState::Reconnecting);
+    run_binance_ws_resilient(tx, state_tx).await;
+    Ok(())
+}
+
+pub struct BinanceWsConnector;
+
+#[async_trait]
+impl Connector for BinanceWsConnector {
+    fn name(&self) -> &str {
+        "binance"
+    }
+
+    async fn run(&self, tx: Sender<MarketTick>, cancel: oneshot::Receiver<()>) -> Result<(), ConnectorError> {
+        let (state_tx, _state_rx) = watch::channel(ConnectionState::Reconnecting);
+        let feed = ReconnectingFeed::new(URL, HEARTBEAT_TIMEOUT);
+        let fut = feed.run(subscribe_msg, parse_tick, tx, state_tx);
+        tokio::pin!(fut);
+        tokio::select! {
+            _ = &mut fut => Ok(()),
+            _ = cancel => Ok(()),
         }
     }
-    Ok(())
 }
@@ -1,48 +1,79 @@
+use async_trait::async_trait;
+use connectors_common::connector::Connector;
+use connectors_common::errors::ConnectorError;
+use connectors_common::reconnect::{ConnectionState, ReconnectingFeed};
 use connectors_common::types::MarketTick;
-use futures::{SinkExt, StreamExt};
-use tokio::sync::mpsc::Sender;
-use tokio_tungstenite::connect_async;
-use tungstenite::Message;
 use serde_json::Value;
-use log::{info, warn};
-
-pub async fn run_coinbase_ws(mut tx: Sender<MarketTick>) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let url = "wss://ws-feed.pro.coinbase.com";
-    info!("Connecting to {}", url);
-    let (ws_stream, _) = connect_async(url).await?;
-    let (mut write, mut read) = ws_stream.split();
-
-    let subscribe = serde_json::json!({
-        "type": "subscribe",
-        "product_ids": ["BTC-USD", "ETH-USD"],
-        "channels": ["ticker"]
-    });
-    let _ = write.send(Message::Text(subscribe.to_string())).await;
-
-    while let Some(msg) = read.next().await {
-        match msg {
-            Ok(Message::Text(txt)) => {
-                if let Ok(v) = serde_json::from_str::<Value>(&txt) {
-                    if v.get("type").and_then(|t| t.as_str()) == Some("ticker") {
-                        let pair = v.get("product_id").and_then(|s| s.as_str()).unwrap_or_default().to_string();
-                        let bid = v.get("best_bid").and_then(|s| s.as_str()).and_then(|s| s.parse::<f64>().ok()).unwrap_or(0.0);
-                        let ask = v.get("best_ask").and_then(|s| s.as_str()).and_then(|s| s.parse::<f64>().ok()).unwrap_or(0.0);
-                        let tick = MarketTick {
-                            exchange: "coinbase".to_string(),
-                            pair,
-                            bid,
-                            ask,
-                            ts: chrono::Utc::now().timestamp_millis() as u128,
-                        };
-                        let _ = tx.send(tick).await;
-                    }
-                } else {
-                    warn!("failed to parse coinbase ws message");
-                }
-            }
-            Ok(_) => {}
-            Err(e) => warn!("coinbase ws error: {:?}", e),
-        }
+use std::time::Duration;
+use tokio::sync::mpsc::Sender;
+use tokio::sync::{oneshot, watch};
+
+const URL: &str = "wss://ws-feed.pro.coinbase.com";
+const HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(15);
+
+fn subscribe_msg() -> Option<String> {
+    Some(
+        serde_json::json!({
+            "type": "subscribe",
+            "product_ids": ["BTC-USD", "ETH-USD"],
+            "channels": ["ticker"]
+        })
+        .to_string(),
+    )
+}
+
+fn parse_tick(text: &str) -> Vec<MarketTick> {
+    let v: Value = match serde_json::from_str(text) {
+        Ok(v) => v,
+        Err(_) => return Vec::new(),
+    };
+    if v.get("type").and_then(|t| t.as_str()) != Some("ticker") {
+        return Vec::new();
     }
+    let pair = v.get("product_id").and_then(|s| s.as_str()).unwrap_or_default().to_string();
+    let bid = v.get("best_bid").and_then(|s| s.as_str()).and_then(|s| s.parse::<f64>().ok()).unwrap_or(0.0);
+    let ask = v.get("best_ask").and_then(|s| s.as_str()).and_then(|s| s.parse::<f64>().ok()).unwrap_or(0.0);
+    vec![MarketTick {
+        exchange: "coinbase".to_string(),
+        pair,
+        bid,
+        ask,
+        ts: chrono::Utc::now().timestamp_millis() as u128,
+    }]
+}
+
+/// Runs the Coinbase ticker feed under a `ReconnectingFeed`: survives disconnects and parse
+/// errors with jittered backoff and force-reconnects on staleness. Never returns under normal
+/// operation (only if `tx` is dropped); `state_tx` exposes connection transitions to callers
+/// that need to gate trading while the feed is down.
+pub async fn run_coinbase_ws_resilient(tx: Sender<MarketTick>, state_tx: watch::Sender<ConnectionState>) {
+    let feed = ReconnectingFeed::new(URL, HEARTBEAT_TIMEOUT);
+    feed.run(subscribe_msg, parse_tick, tx, state_tx).await;
+}
+
+/// Convenience wrapper for callers that don't need connection-state events.
+pub async fn run_coinbase_ws(tx: Sender<MarketTick>) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let (state_tx, _state_rx) = watch::channel(ConnectionState::Reconnecting);
+    run_coinbase_ws_resilient(tx, state_tx).await;
     Ok(())
 }
+
+pub struct CoinbaseWsConnector;
+
+#[async_trait]
+impl Connector for CoinbaseWsConnector {
+    fn name(&self) -> &str {
+        "coinbase"
+    }
+
+    async fn run(&self, tx: Sender<MarketTick>, cancel: oneshot::Receiver<()>) -> Result<(), ConnectorError> {
+        let (state_tx, _state_rx) = watch::channel(ConnectionState::Reconnecting);
+        let feed = ReconnectingFeed::new(URL, HEARTBEAT_TIMEOUT);
+        let fut = feed.run(subscribe_msg, parse_tick, tx, state_tx);
+        tokio::pin!(fut);
+        tokio::select! {
+            _ = &mut fut => Ok(()),
+            _ = cancel => Ok(()),
+        }
+    }
+}
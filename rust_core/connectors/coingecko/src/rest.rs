@@ -1,8 +1,12 @@
+use async_trait::async_trait;
+use connectors_common::connector::Connector;
+use connectors_common::errors::ConnectorError;
 use connectors_common::types::MarketTick;
+use log::info;
 use reqwest::Client;
 use std::time::Duration;
+use tokio::sync::{mpsc::Sender, oneshot};
 use tokio::time::{sleep, Duration as TokioDuration};
-use log::info;
 
 pub async fn run_coingecko_poll(mut tx: tokio::sync::mpsc::Sender<MarketTick>, pairs: Vec<String>, interval_ms: u64) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     let client = Client::new();
@@ -37,3 +41,25 @@ pub async fn run_coingecko_poll(mut tx: tokio::sync::mpsc::Sender<MarketTick>, p
         sleep(interval).await;
     }
 }
+
+/// Polls the configured pairs on a fixed interval; never returns under normal operation.
+pub struct CoingeckoConnector {
+    pub pairs: Vec<String>,
+    pub interval_ms: u64,
+}
+
+#[async_trait]
+impl Connector for CoingeckoConnector {
+    fn name(&self) -> &str {
+        "coingecko"
+    }
+
+    async fn run(&self, tx: Sender<MarketTick>, cancel: oneshot::Receiver<()>) -> Result<(), ConnectorError> {
+        let fut = run_coingecko_poll(tx, self.pairs.clone(), self.interval_ms);
+        tokio::pin!(fut);
+        tokio::select! {
+            res = &mut fut => res.map_err(|e| ConnectorError::Other(e.to_string())),
+            _ = cancel => Ok(()),
+        }
+    }
+}
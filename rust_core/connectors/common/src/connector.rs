@@ -0,0 +1,16 @@
+use crate::errors::ConnectorError;
+use crate::types::MarketTick;
+use async_trait::async_trait;
+use tokio::sync::{mpsc, oneshot};
+
+/// Uniform surface for a live market-data feed: a name for logging/handles, and a `run` that
+/// streams ticks onto `tx` until either the feed ends for good or `cancel` fires. Implementors
+/// reconnect internally on transient errors (see [`crate::reconnect::ReconnectingFeed`] for the
+/// shared WS backoff driver) by racing their reconnect loop against `cancel` in a `select!` —
+/// dropping the handle that owns `cancel`'s sender tears the connection down cleanly. `run`
+/// returning `Ok(())` means a clean, intentional stop (cancelled or the feed ended for good).
+#[async_trait]
+pub trait Connector: Send + Sync {
+    fn name(&self) -> &str;
+    async fn run(&self, tx: mpsc::Sender<MarketTick>, cancel: oneshot::Receiver<()>) -> Result<(), ConnectorError>;
+}
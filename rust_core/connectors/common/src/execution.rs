@@ -0,0 +1,84 @@
+use crate::errors::ConnectorError;
+use async_trait::async_trait;
+use log::warn;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Buy,
+    Sell,
+}
+
+#[derive(Debug, Clone)]
+pub struct OrderRequest {
+    pub pair: String,
+    pub side: Side,
+    pub price: f64,
+    pub qty: f64,
+    /// Caller-assigned idempotency key, carried unchanged across `place_order_sync`'s retries
+    /// (it's part of the cloned `order`) and sent in the venue request body (Kraken's `userref`,
+    /// Coinbase's `client_oid`) so a retry after a client-side timeout - where the first request
+    /// may have already reached and been accepted by the exchange - is recognized as a duplicate
+    /// of the original order instead of resubmitted as a new one.
+    pub client_order_id: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct OrderAck {
+    pub order_id: String,
+    pub status: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct Balance {
+    pub asset: String,
+    pub free: f64,
+    pub locked: f64,
+}
+
+/// Shared surface for acting on a detected arbitrage signal: place/cancel orders and read
+/// balances. Exchange-specific connectors (Kraken, Coinbase, ...) implement this alongside
+/// their existing read-only WS modules.
+#[async_trait]
+pub trait ExecutionClient {
+    async fn place_order(&self, order: OrderRequest) -> Result<OrderAck, ConnectorError>;
+    async fn cancel_order(&self, pair: &str, order_id: &str) -> Result<(), ConnectorError>;
+    async fn balances(&self) -> Result<Vec<Balance>, ConnectorError>;
+}
+
+/// The confirmed, retrying path: await the order ack, retrying with exponential backoff and
+/// re-deriving the nonce/timestamp on transient `ConnectorError::Network` failures.
+#[async_trait]
+pub trait SyncClient: ExecutionClient + Sync {
+    async fn place_order_sync(&self, order: OrderRequest) -> Result<OrderAck, ConnectorError> {
+        let mut backoff = Duration::from_millis(200);
+        let max_attempts = 5;
+        let mut last_err = ConnectorError::Other("no attempts made".to_string());
+        for attempt in 0..max_attempts {
+            match self.place_order(order.clone()).await {
+                Ok(ack) => return Ok(ack),
+                Err(ConnectorError::Network(msg)) => {
+                    warn!("place_order attempt {} failed with network error: {}; retrying in {:?}", attempt + 1, msg, backoff);
+                    last_err = ConnectorError::Network(msg);
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(Duration::from_secs(5));
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Err(last_err)
+    }
+}
+
+/// The fire-and-forget path for latency-critical fills: submits the order without awaiting
+/// confirmation. Implementors must be cheaply cloneable (typically an `Arc`-backed client).
+pub trait AsyncClient: ExecutionClient + Clone + Send + Sync + 'static {
+    fn place_order_async(&self, order: OrderRequest) {
+        let client = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = client.place_order(order).await {
+                warn!("fire-and-forget place_order failed: {:?}", e);
+            }
+        });
+    }
+}
@@ -0,0 +1,5 @@
+pub mod connector;
+pub mod errors;
+pub mod execution;
+pub mod reconnect;
+pub mod types;
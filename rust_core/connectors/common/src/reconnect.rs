@@ -0,0 +1,134 @@
+use crate::types::MarketTick;
+use futures::{SinkExt, StreamExt};
+use log::{info, warn};
+use tokio::sync::{mpsc, watch};
+use tokio::time::{sleep, Duration, Instant};
+use tokio_tungstenite::connect_async;
+use tungstenite::Message;
+
+/// Connection-state event emitted on a side channel so the strategy layer can gate trading
+/// while a feed is down or mid-reconnect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionState {
+    Connected,
+    Reconnecting,
+    Down,
+}
+
+/// Builds the exchange-specific subscription payload sent right after a (re)connect.
+/// Returning `None` means the venue needs no subscribe frame.
+pub trait SubscribeBuilder: Send + Sync {
+    fn build(&self) -> Option<String>;
+}
+
+impl<F: Fn() -> Option<String> + Send + Sync> SubscribeBuilder for F {
+    fn build(&self) -> Option<String> {
+        self()
+    }
+}
+
+/// Parses one text frame into zero or more ticks; a frame that isn't a tick (heartbeat,
+/// subscription ack, ...) simply yields nothing.
+pub trait TickParser: Send + Sync {
+    fn parse(&self, text: &str) -> Vec<MarketTick>;
+}
+
+impl<F: Fn(&str) -> Vec<MarketTick> + Send + Sync> TickParser for F {
+    fn parse(&self, text: &str) -> Vec<MarketTick> {
+        self(text)
+    }
+}
+
+fn jittered(backoff: Duration) -> Duration {
+    let ms = backoff.as_millis() as u64;
+    Duration::from_millis(ms + fastrand::u64(0..=(ms / 2).max(1)))
+}
+
+/// Generic supervised feed driver: wraps `connect_async` in a loop that reconnects with
+/// jittered exponential backoff on disconnect or websocket error, re-sends the venue's
+/// subscription on every (re)connect, and force-reconnects if no tick arrives within
+/// `heartbeat_timeout` (a staleness watchdog). A frame the parser can't turn into ticks just
+/// yields none; it doesn't trigger a reconnect by itself. Connection-state transitions are published on
+/// `state_tx`. This function only returns if `tx` is dropped.
+pub struct ReconnectingFeed {
+    pub url: String,
+    pub heartbeat_timeout: Duration,
+}
+
+impl ReconnectingFeed {
+    pub fn new(url: impl Into<String>, heartbeat_timeout: Duration) -> Self {
+        ReconnectingFeed { url: url.into(), heartbeat_timeout }
+    }
+
+    pub async fn run(
+        &self,
+        subscribe: impl SubscribeBuilder,
+        parser: impl TickParser,
+        tx: mpsc::Sender<MarketTick>,
+        state_tx: watch::Sender<ConnectionState>,
+    ) {
+        let mut backoff = Duration::from_millis(500);
+        loop {
+            let _ = state_tx.send(ConnectionState::Reconnecting);
+            match connect_async(&self.url).await {
+                Ok((ws_stream, _)) => {
+                    info!("connected to {}", self.url);
+                    let _ = state_tx.send(ConnectionState::Connected);
+                    let (mut write, mut read) = ws_stream.split();
+                    if let Some(sub) = subscribe.build() {
+                        let _ = write.send(Message::Text(sub)).await;
+                    }
+
+                    let mut last_tick = Instant::now();
+                    'session: loop {
+                        let remaining = self.heartbeat_timeout.saturating_sub(last_tick.elapsed());
+                        tokio::select! {
+                            msg = read.next() => {
+                                if let Some(Ok(_)) = &msg {
+                                    backoff = Duration::from_millis(500); // reset only once a frame actually arrives
+                                }
+                                match msg {
+                                    Some(Ok(Message::Text(txt))) => {
+                                        let ticks = parser.parse(&txt);
+                                        if !ticks.is_empty() {
+                                            last_tick = Instant::now();
+                                        }
+                                        for tick in ticks {
+                                            if tx.send(tick).await.is_err() {
+                                                return; // receiver dropped: nothing left to feed
+                                            }
+                                        }
+                                    }
+                                    Some(Ok(Message::Ping(payload))) => {
+                                        let _ = write.send(Message::Pong(payload)).await;
+                                    }
+                                    Some(Ok(Message::Close(_))) | None => {
+                                        warn!("feed {} closed, reconnecting", self.url);
+                                        break 'session;
+                                    }
+                                    Some(Err(e)) => {
+                                        warn!("feed {} error: {:?}, reconnecting", self.url, e);
+                                        break 'session;
+                                    }
+                                    _ => {}
+                                }
+                            }
+                            _ = sleep(remaining) => {
+                                if last_tick.elapsed() >= self.heartbeat_timeout {
+                                    warn!("feed {} stale (no tick in {:?}), forcing reconnect", self.url, self.heartbeat_timeout);
+                                    break 'session;
+                                }
+                            }
+                        }
+                    }
+                }
+                Err(e) => {
+                    warn!("connect error on {}: {:?}", self.url, e);
+                }
+            }
+            let _ = state_tx.send(ConnectionState::Down);
+            sleep(jittered(backoff)).await;
+            backoff = (backoff * 2).min(Duration::from_secs(30));
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use rust_core::orderbook::Decimal;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,3 +24,29 @@ pub struct OrderBookSnapshot {
     pub asks: Vec<OrderBookLevel>,
     pub ts: u128,
 }
+
+/// One venue diff-depth update: an ordered batch of level changes plus the venue's
+/// update-sequence bounds for gap detection (Binance's per-diff `U`/`u`; a venue with a single
+/// sequence field maps it to both `first_update_id` and `last_update_id`).
+#[derive(Debug, Clone)]
+pub struct DepthDiff {
+    pub exchange: String,
+    pub pair: String,
+    pub first_update_id: u64,
+    pub last_update_id: u64,
+    pub bids: Vec<(Decimal, Decimal)>,
+    pub asks: Vec<(Decimal, Decimal)>,
+    pub ts: i64,
+}
+
+/// A REST depth snapshot used to (re)seed a live book, carrying the sequence id it was taken at
+/// so diffs older than the snapshot can be dropped and the gap check can resume from it.
+#[derive(Debug, Clone)]
+pub struct DepthSnapshot {
+    pub exchange: String,
+    pub pair: String,
+    pub last_update_id: u64,
+    pub bids: Vec<(Decimal, Decimal)>,
+    pub asks: Vec<(Decimal, Decimal)>,
+    pub ts: i64,
+}
@@ -0,0 +1,131 @@
+use connectors_common::errors::ConnectorError;
+use connectors_common::execution::{AsyncClient, Balance, ExecutionClient, OrderAck, OrderRequest, Side, SyncClient};
+use async_trait::async_trait;
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use reqwest::Client;
+use sha2::{Digest, Sha256, Sha512};
+use std::hash::{Hash, Hasher};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const API_BASE: &str = "https://api.kraken.com";
+
+/// Kraken's private REST trading client. Signs requests per Kraken's `API-Sign` scheme:
+/// HMAC-SHA512(base64-decoded secret, path + SHA256(nonce + post_data)), base64 encoded.
+#[derive(Clone)]
+pub struct KrakenExecutionClient {
+    api_key: String,
+    api_secret: String, // base64-encoded, as issued by Kraken
+    http: Client,
+}
+
+impl KrakenExecutionClient {
+    pub fn new(api_key: String, api_secret: String) -> Self {
+        KrakenExecutionClient { api_key, api_secret, http: Client::new() }
+    }
+
+    /// A fresh nonce (milliseconds since epoch); Kraken rejects a replayed or non-increasing
+    /// nonce, so every retry must re-derive this. Two calls within the same millisecond get the
+    /// same value.
+    fn nonce() -> u128 {
+        SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis()
+    }
+
+    /// Kraken's `userref` is a 32-bit signed integer, so a caller-assigned string
+    /// `client_order_id` is hashed down to one deterministically - same id, same `userref`,
+    /// every retry - letting Kraken's own userref-based order lookup catch a duplicate
+    /// resubmission of an order that actually went through on a prior, timed-out attempt.
+    fn userref(client_order_id: &str) -> i32 {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        client_order_id.hash(&mut hasher);
+        (hasher.finish() as u32) as i32
+    }
+
+    fn sign(&self, path: &str, post_data: &str) -> Result<String, ConnectorError> {
+        let secret = base64::engine::general_purpose::STANDARD
+            .decode(&self.api_secret)
+            .map_err(|e| ConnectorError::Other(format!("invalid api secret: {:?}", e)))?;
+
+        let mut sha256 = Sha256::new();
+        sha256.update(post_data.as_bytes());
+        let nonce_and_data_hash = sha256.finalize();
+
+        let mut mac = Hmac::<Sha512>::new_from_slice(&secret).map_err(|e| ConnectorError::Other(format!("hmac init: {:?}", e)))?;
+        mac.update(path.as_bytes());
+        mac.update(&nonce_and_data_hash);
+        Ok(base64::engine::general_purpose::STANDARD.encode(mac.finalize().into_bytes()))
+    }
+
+    async fn private_request(&self, endpoint: &str, mut form: Vec<(String, String)>) -> Result<serde_json::Value, ConnectorError> {
+        let path = format!("/0/private/{}", endpoint);
+        let nonce = Self::nonce().to_string();
+        form.insert(0, ("nonce".to_string(), nonce.clone()));
+        let post_data = serde_urlencoded::to_string(&form).map_err(|e| ConnectorError::Other(format!("encode form: {:?}", e)))?;
+        // Kraken signs nonce + post_data, not post_data alone.
+        let signed = format!("{}{}", nonce, post_data);
+        let signature = self.sign(&path, &signed)?;
+
+        let resp = self
+            .http
+            .post(format!("{}{}", API_BASE, path))
+            .header("API-Key", &self.api_key)
+            .header("API-Sign", signature)
+            .form(&form)
+            .send()
+            .await
+            .map_err(|e| ConnectorError::Network(format!("{:?}", e)))?;
+
+        let v: serde_json::Value = resp.json().await.map_err(|e| ConnectorError::Parse(format!("{:?}", e)))?;
+        if let Some(errors) = v.get("error").and_then(|e| e.as_array()) {
+            if !errors.is_empty() {
+                return Err(ConnectorError::Other(format!("kraken error: {:?}", errors)));
+            }
+        }
+        Ok(v)
+    }
+}
+
+#[async_trait]
+impl ExecutionClient for KrakenExecutionClient {
+    async fn place_order(&self, order: OrderRequest) -> Result<OrderAck, ConnectorError> {
+        let form = vec![
+            ("pair".to_string(), order.pair.clone()),
+            ("type".to_string(), if order.side == Side::Buy { "buy".to_string() } else { "sell".to_string() }),
+            ("ordertype".to_string(), "limit".to_string()),
+            ("price".to_string(), order.price.to_string()),
+            ("volume".to_string(), order.qty.to_string()),
+            ("userref".to_string(), Self::userref(&order.client_order_id).to_string()),
+        ];
+        let v = self.private_request("AddOrder", form).await?;
+        let order_id = v
+            .get("result")
+            .and_then(|r| r.get("txid"))
+            .and_then(|t| t.as_array())
+            .and_then(|a| a.first())
+            .and_then(|id| id.as_str())
+            .unwrap_or_default()
+            .to_string();
+        Ok(OrderAck { order_id, status: "submitted".to_string() })
+    }
+
+    async fn cancel_order(&self, _pair: &str, order_id: &str) -> Result<(), ConnectorError> {
+        let form = vec![("txid".to_string(), order_id.to_string())];
+        self.private_request("CancelOrder", form).await?;
+        Ok(())
+    }
+
+    async fn balances(&self) -> Result<Vec<Balance>, ConnectorError> {
+        let v = self.private_request("Balance", vec![]).await?;
+        let result = v.get("result").and_then(|r| r.as_object()).cloned().unwrap_or_default();
+        Ok(result
+            .into_iter()
+            .filter_map(|(asset, amount)| {
+                let free = amount.as_str()?.parse::<f64>().ok()?;
+                Some(Balance { asset, free, locked: 0.0 })
+            })
+            .collect())
+    }
+}
+
+impl SyncClient for KrakenExecutionClient {}
+impl AsyncClient for KrakenExecutionClient {}
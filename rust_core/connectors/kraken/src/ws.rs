@@ -1,57 +1,84 @@
+use async_trait::async_trait;
+use connectors_common::connector::Connector;
+use connectors_common::errors::ConnectorError;
+use connectors_common::reconnect::{ConnectionState, ReconnectingFeed};
 use connectors_common::types::MarketTick;
-use futures::{SinkExt, StreamExt};
-use tokio::sync::mpsc::Sender;
-use tokio_tungstenite::connect_async;
-use tungstenite::Message;
 use serde_json::Value;
-use log::{info, warn};
-
-pub async fn run_kraken_ws(mut tx: Sender<MarketTick>) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let url = "wss://ws.kraken.com";
-    info!("Connecting to {}", url);
-    let (ws_stream, _) = connect_async(url).await?;
-    let (mut write, mut read) = ws_stream.split();
-
-    let subscribe = serde_json::json!({
-        "event": "subscribe",
-        "subscription": { "name": "ticker" },
-        "pair": ["XBT/USD","ETH/USD"]
-    });
-    let _ = write.send(Message::Text(subscribe.to_string())).await;
-
-    while let Some(msg) = read.next().await {
-        match msg {
-            Ok(Message::Text(txt)) => {
-                if let Ok(v) = serde_json::from_str::<Value>(&txt) {
-                    if v.is_array() {
-                        if let Some(arr) = v.as_array() {
-                            if arr.len() >= 3 {
-                                let pair = arr[2].as_str().unwrap_or_default().to_string();
-                                let data = &arr[1];
-                                if let (Some(bid), Some(ask)) = (
-                                    data.get("b").and_then(|v| v.as_array()).and_then(|a| a.get(0)).and_then(|s| s.as_str()).and_then(|s| s.parse::<f64>().ok()),
-                                    data.get("a").and_then(|v| v.as_array()).and_then(|a| a.get(0)).and_then(|s| s.as_str()).and_then(|s| s.parse::<f64>().ok())
-                                ) {
-                                    let tick = MarketTick {
-                                        exchange: "kraken".to_string(),
-                                        pair,
-                                        bid,
-                                        ask,
-                                        ts: chrono::Utc::now().timestamp_millis() as u128,
-                                    };
-                                    let _ = tx.send(tick).await;
-                                }
-                            }
-                        }
-                    } else {
-                    }
-                } else {
-                    warn!("failed to parse kraken ws msg");
-                }
-            }
-            Ok(_) => {}
-            Err(e) => warn!("kraken ws error: {:?}", e),
-        }
+use std::time::Duration;
+use tokio::sync::mpsc::Sender;
+use tokio::sync::{oneshot, watch};
+
+const URL: &str = "wss://ws.kraken.com";
+const HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(15);
+
+fn subscribe_msg() -> Option<String> {
+    Some(
+        serde_json::json!({
+            "event": "subscribe",
+            "subscription": { "name": "ticker" },
+            "pair": ["XBT/USD","ETH/USD"]
+        })
+        .to_string(),
+    )
+}
+
+fn parse_tick(text: &str) -> Vec<MarketTick> {
+    let v: Value = match serde_json::from_str(text) {
+        Ok(v) => v,
+        Err(_) => return Vec::new(),
+    };
+    let arr = match v.as_array() {
+        Some(a) if a.len() >= 3 => a,
+        _ => return Vec::new(),
+    };
+    let pair = arr[2].as_str().unwrap_or_default().to_string();
+    let data = &arr[1];
+    let bid = data.get("b").and_then(|v| v.as_array()).and_then(|a| a.first()).and_then(|s| s.as_str()).and_then(|s| s.parse::<f64>().ok());
+    let ask = data.get("a").and_then(|v| v.as_array()).and_then(|a| a.first()).and_then(|s| s.as_str()).and_then(|s| s.parse::<f64>().ok());
+    match (bid, ask) {
+        (Some(bid), Some(ask)) => vec![MarketTick {
+            exchange: "kraken".to_string(),
+            pair,
+            bid,
+            ask,
+            ts: chrono::Utc::now().timestamp_millis() as u128,
+        }],
+        _ => Vec::new(),
     }
+}
+
+/// Runs the Kraken ticker feed under a `ReconnectingFeed`: survives disconnects and parse
+/// errors with jittered backoff and force-reconnects on staleness. Never returns under normal
+/// operation (only if `tx` is dropped); `state_tx` exposes connection transitions to callers
+/// that need to gate trading while the feed is down.
+pub async fn run_kraken_ws_resilient(tx: Sender<MarketTick>, state_tx: watch::Sender<ConnectionState>) {
+    let feed = ReconnectingFeed::new(URL, HEARTBEAT_TIMEOUT);
+    feed.run(subscribe_msg, parse_tick, tx, state_tx).await;
+}
+
+/// Convenience wrapper for callers that don't need connection-state events.
+pub async fn run_kraken_ws(tx: Sender<MarketTick>) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let (state_tx, _state_rx) = watch::channel(ConnectionState::Reconnecting);
+    run_kraken_ws_resilient(tx, state_tx).await;
     Ok(())
 }
+
+pub struct KrakenWsConnector;
+
+#[async_trait]
+impl Connector for KrakenWsConnector {
+    fn name(&self) -> &str {
+        "kraken"
+    }
+
+    async fn run(&self, tx: Sender<MarketTick>, cancel: oneshot::Receiver<()>) -> Result<(), ConnectorError> {
+        let (state_tx, _state_rx) = watch::channel(ConnectionState::Reconnecting);
+        let feed = ReconnectingFeed::new(URL, HEARTBEAT_TIMEOUT);
+        let fut = feed.run(subscribe_msg, parse_tick, tx, state_tx);
+        tokio::pin!(fut);
+        tokio::select! {
+            _ = &mut fut => Ok(()),
+            _ = cancel => Ok(()),
+        }
+    }
+}
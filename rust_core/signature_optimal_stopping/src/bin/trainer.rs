@@ -50,6 +50,18 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let params = &v["params"];
     let trunc = params["truncation"].as_u64().unwrap_or(2) as usize;
     let ridge = params["ridge"].as_f64().unwrap_or(1e-3);
+    let backend = match params["backend"].as_str() {
+        Some("gbdt") => signature_optimal_stopping::RegressorBackend::Gbdt,
+        _ => signature_optimal_stopping::RegressorBackend::Ridge,
+    };
+    let n_estimators = params["n_estimators"].as_u64().unwrap_or(100) as usize;
+    let max_depth = params["max_depth"].as_u64().unwrap_or(3) as usize;
+    let learning_rate = params["learning_rate"].as_f64().unwrap_or(0.1);
+    let feature_config: signature_optimal_stopping::FeatureConfig = params
+        .get("feature_config")
+        .map(|fc| serde_json::from_value(fc.clone()))
+        .transpose()?
+        .unwrap_or_default();
     let samples_v = v["samples"].as_array().ok_or("samples must be array")?;
 
     // convert to TrainingSample
@@ -67,14 +79,22 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
 
     let d = samples[0].traj[0].len();
-    let dim = signature_optimal_stopping::compute_feature_dim(d, trunc);
-    let mut stopper = signature_optimal_stopping::SignatureStopper::new(signature_optimal_stopping::SigParams { truncation: trunc, ridge }, dim);
+    let dim = signature_optimal_stopping::compute_feature_dim(d, trunc, &feature_config);
+    let sig_params = signature_optimal_stopping::SigParams {
+        truncation: trunc,
+        ridge,
+        backend,
+        n_estimators,
+        max_depth,
+        learning_rate,
+        feature_config,
+    };
+    let mut stopper = signature_optimal_stopping::SignatureStopper::new(sig_params.clone(), dim);
     stopper.train(&samples)?;
 
-    // write weights as json
-    let weights = stopper.weights.ok_or("missing weights")?;
-    let w_vec: Vec<f64> = weights.to_vec();
-    let out = serde_json::json!({ "weights": w_vec, "params": { "truncation": trunc, "ridge": ridge }});
+    // write the trained model (ridge weights or gbdt ensemble) as json
+    let model = stopper.model.ok_or("missing trained model")?;
+    let out = serde_json::json!({ "model": model, "params": sig_params });
     write(outpath, serde_json::to_string(&out)?)?;
     println!("Written weights to {}", outpath);
     Ok(())
@@ -1,33 +1,99 @@
 //! signature_optimal_stopping
 //!
-//! Self-contained implementation (truncated signature up to level 3 + simple ridge regression)
+//! Self-contained implementation (truncated signature up to level 3 + a pluggable regressor)
 //! to approximate a continuation function and produce an optimal stopping rule based on signatures.
 //!
 //! This implementation follows the algorithmic idea of using truncated signatures as features and
-//! fitting a regression to estimate continuation values. It is intentionally compact and suitable
-//! for testing and integration. For production, replace signature computation with a specialized
-//! optimized library.
+//! fitting a regression to estimate continuation values. The regressor backend is selectable via
+//! `SigParams::backend`: plain ridge regression, or a gradient-boosted tree ensemble for nonlinear,
+//! regime-dependent payoffs. It is intentionally compact and suitable for testing and integration.
+//! For production, replace signature computation with a specialized optimized library.
 
 use ndarray::{Array1, Array2, Axis};
 use ndarray_linalg::solve::Inverse;
+use rustfft::{num_complex::Complex, FftPlanner};
 use serde::{Deserialize, Serialize};
 use std::error::Error;
 use std::fmt;
 use chrono::Utc;
 use log::info;
 
+/// Which regressor fits the continuation-value function on top of the feature vector.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RegressorBackend {
+    Ridge,
+    Gbdt,
+}
+
+impl Default for RegressorBackend {
+    fn default() -> Self {
+        RegressorBackend::Ridge
+    }
+}
+
+/// Controls which feature blocks are emitted by [`compute_features`]. Signature features
+/// capture iterated-integral moments; spectral features capture the dominant cycle energy
+/// of the window via FFT magnitudes. The layout is always signature-block-then-spectral-block
+/// so trained weights stay aligned across runs with the same config.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeatureConfig {
+    pub use_signature: bool,
+    pub use_spectral: bool,
+    /// Length the increment series is zero-padded/truncated to before the FFT. Should be a
+    /// power of two (e.g. 64).
+    #[serde(default = "default_spectral_window")]
+    pub spectral_window: usize,
+    /// Number of leading frequency bins (per dimension) appended to the feature vector.
+    #[serde(default = "default_spectral_bins")]
+    pub spectral_bins: usize,
+}
+
+fn default_spectral_window() -> usize { 64 }
+fn default_spectral_bins() -> usize { 8 }
+
+impl Default for FeatureConfig {
+    fn default() -> Self {
+        FeatureConfig {
+            use_signature: true,
+            use_spectral: false,
+            spectral_window: default_spectral_window(),
+            spectral_bins: default_spectral_bins(),
+        }
+    }
+}
+
 /// Parameters and types
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SigParams {
     pub truncation: usize, // truncation level (1..3 supported)
     pub ridge: f64,        // ridge regularization
+    #[serde(default)]
+    pub backend: RegressorBackend,
+    #[serde(default = "default_n_estimators")]
+    pub n_estimators: usize,
+    #[serde(default = "default_max_depth")]
+    pub max_depth: usize,
+    #[serde(default = "default_learning_rate")]
+    pub learning_rate: f64,
+    #[serde(default)]
+    pub feature_config: FeatureConfig,
 }
 
+fn default_n_estimators() -> usize { 100 }
+fn default_max_depth() -> usize { 3 }
+fn default_learning_rate() -> f64 { 0.1 }
+
 impl Default for SigParams {
     fn default() -> Self {
         SigParams {
             truncation: 3,
             ridge: 1e-3,
+            backend: RegressorBackend::Ridge,
+            n_estimators: default_n_estimators(),
+            max_depth: default_max_depth(),
+            learning_rate: default_learning_rate(),
+            feature_config: FeatureConfig::default(),
         }
     }
 }
@@ -124,6 +190,46 @@ pub fn compute_truncated_signature(traj: &Trajectory, trunc: usize) -> Result<Ve
     Ok(feat)
 }
 
+/// Compute spectral (FFT) features: for each dimension of the trajectory, zero-pad/truncate
+/// the increment series to `window` samples, run a forward FFT, and append the magnitudes
+/// (normalized by `window`) of the first `bins` frequency bins. Returns `d * min(bins, window)`
+/// entries, grouped by dimension.
+pub fn compute_spectral_features(traj: &Trajectory, window: usize, bins: usize) -> Result<Vec<f64>, SigError> {
+    if traj.is_empty() {
+        return Err(SigError::BadInput("empty trajectory".to_string()));
+    }
+    let d = traj[0].len();
+    let mut planner = FftPlanner::<f64>::new();
+    let fft = planner.plan_fft_forward(window);
+
+    let mut feat = Vec::with_capacity(d * bins);
+    for k in 0..d {
+        let mut buffer: Vec<Complex<f64>> = vec![Complex::new(0.0, 0.0); window];
+        for i in 1..traj.len().min(window + 1) {
+            buffer[i - 1] = Complex::new(traj[i][k] - traj[i - 1][k], 0.0);
+        }
+        fft.process(&mut buffer);
+        for bin in 0..bins.min(window) {
+            feat.push(buffer[bin].norm() / window as f64);
+        }
+    }
+    Ok(feat)
+}
+
+/// Build the full feature vector for a trajectory per `FeatureConfig`: the signature block
+/// (if enabled) followed by the spectral block (if enabled). The order is fixed so that
+/// `compute_feature_dim` and trained weights stay aligned.
+pub fn compute_features(traj: &Trajectory, trunc: usize, config: &FeatureConfig) -> Result<Vec<f64>, SigError> {
+    let mut feat = Vec::new();
+    if config.use_signature {
+        feat.extend(compute_truncated_signature(traj, trunc)?);
+    }
+    if config.use_spectral {
+        feat.extend(compute_spectral_features(traj, config.spectral_window, config.spectral_bins)?);
+    }
+    Ok(feat)
+}
+
 /// Fit a ridge regression: solves (X^T X + lambda I) w = X^T y.
 /// X: (n_samples x n_features), y: (n_samples)
 pub fn fit_ridge(X: &Array2<f64>, y: &Array1<f64>, lambda: f64) -> Result<Array1<f64>, SigError> {
@@ -156,6 +262,200 @@ pub fn score_feature_vec(w: &Array1<f64>, x: &Array1<f64>) -> f64 {
     w.dot(x)
 }
 
+/// A node of a depth-limited CART regression tree, fit greedily on variance reduction.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum TreeNode {
+    Leaf {
+        value: f64,
+    },
+    Split {
+        feature: usize,
+        threshold: f64,
+        left: Box<TreeNode>,
+        right: Box<TreeNode>,
+    },
+}
+
+impl TreeNode {
+    pub fn predict(&self, x: &Array1<f64>) -> f64 {
+        match self {
+            TreeNode::Leaf { value } => *value,
+            TreeNode::Split { feature, threshold, left, right } => {
+                if x[*feature] <= *threshold {
+                    left.predict(x)
+                } else {
+                    right.predict(x)
+                }
+            }
+        }
+    }
+}
+
+/// The full boosted ensemble: every stage's tree plus the base value and learning rate used to
+/// combine them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GbdtModel {
+    pub base_value: f64,
+    pub learning_rate: f64,
+    pub trees: Vec<TreeNode>,
+    /// Width of the feature vector `fit_gbdt` was trained on, recorded so `ModelKind::feature_dim`
+    /// can validate it the same way `Ridge`'s weight-vector length does.
+    pub feature_dim: usize,
+}
+
+impl GbdtModel {
+    pub fn predict(&self, x: &Array1<f64>) -> f64 {
+        let mut f = self.base_value;
+        for tree in &self.trees {
+            f += self.learning_rate * tree.predict(x);
+        }
+        f
+    }
+}
+
+/// Trained regression backend: either the linear ridge weights or a gradient-boosted
+/// tree ensemble over the same feature vector.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ModelKind {
+    Ridge(Array1<f64>),
+    Gbdt(GbdtModel),
+}
+
+impl ModelKind {
+    pub fn feature_dim(&self) -> Option<usize> {
+        match self {
+            ModelKind::Ridge(w) => Some(w.len()),
+            ModelKind::Gbdt(m) => Some(m.feature_dim),
+        }
+    }
+
+    pub fn predict(&self, x: &Array1<f64>) -> f64 {
+        match self {
+            ModelKind::Ridge(w) => score_feature_vec(w, x),
+            ModelKind::Gbdt(m) => m.predict(x),
+        }
+    }
+}
+
+/// Greedily split `(x, r)` on the feature/threshold that maximizes variance reduction,
+/// recursing down to `max_depth`. Candidate thresholds are the midpoints between a
+/// feature's sorted sample values.
+fn fit_tree(x: &Array2<f64>, r: &Array1<f64>, depth: usize, max_depth: usize) -> TreeNode {
+    let n = r.len();
+    let leaf_value = r.sum() / n as f64;
+    if depth >= max_depth || n < 2 {
+        return TreeNode::Leaf { value: leaf_value };
+    }
+
+    let total_var = r.iter().map(|v| (v - leaf_value).powi(2)).sum::<f64>();
+    if total_var <= 1e-12 {
+        return TreeNode::Leaf { value: leaf_value };
+    }
+
+    let p = x.ncols();
+    let mut best: Option<(usize, f64, f64)> = None; // (feature, threshold, variance reduction)
+
+    for feature in 0..p {
+        let mut values: Vec<f64> = x.column(feature).iter().cloned().collect();
+        values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        values.dedup();
+        for w in values.windows(2) {
+            let threshold = (w[0] + w[1]) / 2.0;
+            let mut left_sum = 0.0;
+            let mut left_n = 0usize;
+            let mut right_sum = 0.0;
+            let mut right_n = 0usize;
+            for i in 0..n {
+                if x[[i, feature]] <= threshold {
+                    left_sum += r[i];
+                    left_n += 1;
+                } else {
+                    right_sum += r[i];
+                    right_n += 1;
+                }
+            }
+            if left_n == 0 || right_n == 0 {
+                continue;
+            }
+            let left_mean = left_sum / left_n as f64;
+            let right_mean = right_sum / right_n as f64;
+            let mut residual_var = 0.0;
+            for i in 0..n {
+                let mean = if x[[i, feature]] <= threshold { left_mean } else { right_mean };
+                residual_var += (r[i] - mean).powi(2);
+            }
+            let reduction = total_var - residual_var;
+            if best.map(|(_, _, best_reduction)| reduction > best_reduction).unwrap_or(true) {
+                best = Some((feature, threshold, reduction));
+            }
+        }
+    }
+
+    match best {
+        Some((feature, threshold, reduction)) if reduction > 1e-12 => {
+            let mut left_rows = Vec::new();
+            let mut right_rows = Vec::new();
+            for i in 0..n {
+                if x[[i, feature]] <= threshold {
+                    left_rows.push(i);
+                } else {
+                    right_rows.push(i);
+                }
+            }
+            let gather = |rows: &[usize]| -> (Array2<f64>, Array1<f64>) {
+                let mut xs = Array2::<f64>::zeros((rows.len(), p));
+                let mut rs = Array1::<f64>::zeros(rows.len());
+                for (out_i, &i) in rows.iter().enumerate() {
+                    xs.row_mut(out_i).assign(&x.row(i));
+                    rs[out_i] = r[i];
+                }
+                (xs, rs)
+            };
+            let (lx, lr) = gather(&left_rows);
+            let (rx, rr) = gather(&right_rows);
+            TreeNode::Split {
+                feature,
+                threshold,
+                left: Box::new(fit_tree(&lx, &lr, depth + 1, max_depth)),
+                right: Box::new(fit_tree(&rx, &rr, depth + 1, max_depth)),
+            }
+        }
+        _ => TreeNode::Leaf { value: leaf_value },
+    }
+}
+
+/// Fit a gradient-boosted regression tree ensemble: initialize to the mean reward, then
+/// repeatedly fit a tree to the negative gradient (residual) of squared loss and shrink
+/// it into the running prediction by `learning_rate`.
+pub fn fit_gbdt(
+    x: &Array2<f64>,
+    y: &Array1<f64>,
+    n_estimators: usize,
+    max_depth: usize,
+    learning_rate: f64,
+) -> Result<GbdtModel, SigError> {
+    let n = x.nrows();
+    if y.len() != n {
+        return Err(SigError::BadInput("X/y size mismatch".to_string()));
+    }
+    if n == 0 {
+        return Err(SigError::BadInput("no training samples".to_string()));
+    }
+    let feature_dim = x.ncols();
+    let base_value = y.sum() / n as f64;
+    let mut f = Array1::<f64>::from_elem(n, base_value);
+    let mut trees = Vec::with_capacity(n_estimators);
+    for _ in 0..n_estimators {
+        let residual = y - &f;
+        let tree = fit_tree(x, &residual, 0, max_depth);
+        for i in 0..n {
+            f[i] += learning_rate * tree.predict(&x.row(i).to_owned());
+        }
+        trees.push(tree);
+    }
+    Ok(GbdtModel { base_value, learning_rate, trees, feature_dim })
+}
+
 /// Dataset type for training: each sample is (trajectory, label)
 #[derive(Clone, Serialize, Deserialize)]
 pub struct TrainingSample {
@@ -163,10 +463,169 @@ pub struct TrainingSample {
     pub reward: f64, // observed reward if stopped at that time (target)
 }
 
-/// Trainer object: holds params and trained weights
+/// Common interface for a stopping-rule detector: something that can (optionally) be trained
+/// on labeled trajectories, scored against a live trajectory to produce a continuation value,
+/// and queried for a stop/continue decision. `SignatureStopper`, `ThresholdUnit` and
+/// `PatternUnit` are the three analytic units shipped in this crate; swap between them at
+/// runtime via an [`AnalyticUnitConfig`].
+pub trait AnalyticUnit {
+    fn train(&mut self, samples: &[TrainingSample]) -> Result<(), SigError>;
+    fn score(&self, traj: &Trajectory) -> Result<f64, SigError>;
+    /// Default decision rule: stop if immediate reward >= continuation_score (or thresholded).
+    fn should_stop(&self, traj: &Trajectory, immediate_reward: f64, threshold: f64) -> Result<bool, SigError> {
+        let cont = self.score(traj)?;
+        Ok(immediate_reward >= cont - threshold)
+    }
+    /// Enables downcasting a `&dyn AnalyticUnit` back to its concrete type (e.g. to read out
+    /// `SignatureStopper`'s trained weights).
+    fn as_any(&self) -> &dyn std::any::Any;
+}
+
+/// A fixed polynomial-threshold rule over a scalar path: no training, just the window +
+/// threshold used by the original standalone `stopping_index` function.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThresholdUnit {
+    pub window: usize,
+    pub threshold: f64,
+}
+
+impl ThresholdUnit {
+    pub fn new(window: usize, threshold: f64) -> Self {
+        ThresholdUnit { window, threshold }
+    }
+
+    /// Polynomial 'signature' score over the trailing `window` of a scalar path (same moments
+    /// as the standalone `stopping_index` rule).
+    fn window_score(&self, path: &[f64]) -> Option<f64> {
+        if path.len() < self.window {
+            return None;
+        }
+        let w = &path[path.len() - self.window..];
+        let x0 = w[0];
+        let dx: Vec<f64> = w.iter().map(|v| v - x0).collect();
+        let m1 = dx.iter().sum::<f64>() / self.window as f64;
+        let m2 = dx.iter().map(|d| d * d).sum::<f64>() / self.window as f64;
+        let m3 = dx.iter().map(|d| d * d * d).sum::<f64>() / self.window as f64;
+        Some(m1 + 0.5 * m2.signum() * m2.sqrt() + 0.1 * m3)
+    }
+}
+
+impl AnalyticUnit for ThresholdUnit {
+    fn train(&mut self, _samples: &[TrainingSample]) -> Result<(), SigError> {
+        Ok(()) // stateless rule: nothing to fit
+    }
+
+    fn score(&self, traj: &Trajectory) -> Result<f64, SigError> {
+        if traj.is_empty() {
+            return Err(SigError::BadInput("empty trajectory".to_string()));
+        }
+        let path: Vec<f64> = traj.iter().map(|p| p[0]).collect();
+        self.window_score(&path).ok_or_else(|| SigError::BadInput("trajectory shorter than window".to_string()))
+    }
+
+    fn should_stop(&self, traj: &Trajectory, _immediate_reward: f64, _threshold: f64) -> Result<bool, SigError> {
+        Ok(self.score(traj)? > self.threshold)
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+/// Euclidean distance between two trajectories over their shared length/dimension prefix.
+fn trajectory_distance(a: &Trajectory, b: &Trajectory) -> f64 {
+    let n = a.len().min(b.len());
+    if n == 0 {
+        return f64::INFINITY;
+    }
+    let mut sum = 0.0;
+    for i in 0..n {
+        let d = a[i].len().min(b[i].len());
+        for k in 0..d {
+            sum += (a[i][k] - b[i][k]).powi(2);
+        }
+    }
+    sum.sqrt()
+}
+
+/// Stub nearest-match detector: stores labeled pattern/anti-pattern trajectories and scores a
+/// new trajectory by how much closer it is to the nearest pattern than to the nearest
+/// anti-pattern.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PatternUnit {
+    pub patterns: Vec<Trajectory>,
+    pub anti_patterns: Vec<Trajectory>,
+}
+
+impl PatternUnit {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn nearest_distance(&self, traj: &Trajectory, set: &[Trajectory]) -> Option<f64> {
+        set.iter().map(|p| trajectory_distance(traj, p)).fold(None, |acc, d| match acc {
+            None => Some(d),
+            Some(best) => Some(best.min(d)),
+        })
+    }
+}
+
+impl AnalyticUnit for PatternUnit {
+    /// Labels training samples by sign of reward: positive reward trajectories become
+    /// patterns (favorable stopping points), non-positive become anti-patterns.
+    fn train(&mut self, samples: &[TrainingSample]) -> Result<(), SigError> {
+        self.patterns.clear();
+        self.anti_patterns.clear();
+        for s in samples {
+            if s.reward > 0.0 {
+                self.patterns.push(s.traj.clone());
+            } else {
+                self.anti_patterns.push(s.traj.clone());
+            }
+        }
+        Ok(())
+    }
+
+    fn score(&self, traj: &Trajectory) -> Result<f64, SigError> {
+        if traj.is_empty() {
+            return Err(SigError::BadInput("empty trajectory".to_string()));
+        }
+        let d_pattern = self.nearest_distance(traj, &self.patterns).unwrap_or(f64::INFINITY);
+        let d_anti = self.nearest_distance(traj, &self.anti_patterns).unwrap_or(f64::INFINITY);
+        Ok(d_anti - d_pattern)
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+/// Serde-tagged config so an analytic unit can be selected, constructed, and serialized at
+/// runtime (e.g. from a Python-supplied params JSON).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "unit", rename_all = "lowercase")]
+pub enum AnalyticUnitConfig {
+    Signature { params: SigParams, feature_dim: usize },
+    Threshold { window: usize, threshold: f64 },
+    Pattern,
+}
+
+impl AnalyticUnitConfig {
+    pub fn build(&self) -> Box<dyn AnalyticUnit + Send> {
+        match self {
+            AnalyticUnitConfig::Signature { params, feature_dim } => {
+                Box::new(SignatureStopper::new(params.clone(), *feature_dim))
+            }
+            AnalyticUnitConfig::Threshold { window, threshold } => Box::new(ThresholdUnit::new(*window, *threshold)),
+            AnalyticUnitConfig::Pattern => Box::new(PatternUnit::new()),
+        }
+    }
+}
+
+/// Trainer object: holds params and the trained regression backend
 pub struct SignatureStopper {
     pub params: SigParams,
-    pub weights: Option<Array1<f64>>,
+    pub model: Option<ModelKind>,
     pub feature_dim: usize,
 }
 
@@ -174,7 +633,7 @@ impl SignatureStopper {
     pub fn new(params: SigParams, feature_dim: usize) -> Self {
         SignatureStopper {
             params,
-            weights: None,
+            model: None,
             feature_dim,
         }
     }
@@ -189,7 +648,7 @@ impl SignatureStopper {
         let mut x = Array2::<f64>::zeros((n, p));
         let mut y = Array1::<f64>::zeros(n);
         for (i, s) in samples.iter().enumerate() {
-            let feat = compute_truncated_signature(&s.traj, self.params.truncation)?;
+            let feat = compute_features(&s.traj, self.params.truncation, &self.params.feature_config)?;
             if feat.len() != p {
                 return Err(SigError::BadInput(format!("feature dim mismatch: got {}, expected {}", feat.len(), p)));
             }
@@ -201,25 +660,36 @@ impl SignatureStopper {
         Ok((x, y))
     }
 
-    /// Train using ridge regression on samples.
+    /// Train the configured regression backend (ridge or gradient-boosted trees) on samples.
     pub fn train(&mut self, samples: &[TrainingSample]) -> Result<(), SigError> {
         let (x, y) = self.build_design_matrix(samples)?;
-        let w = fit_ridge(&x, &y, self.params.ridge)?;
-        self.weights = Some(w);
-        info!("Trained weights (len={}): trained at {}", self.weights.as_ref().unwrap().len(), Utc::now());
+        let model = match self.params.backend {
+            RegressorBackend::Ridge => {
+                let w = fit_ridge(&x, &y, self.params.ridge)?;
+                info!("Trained ridge weights (len={}): trained at {}", w.len(), Utc::now());
+                ModelKind::Ridge(w)
+            }
+            RegressorBackend::Gbdt => {
+                let m = fit_gbdt(&x, &y, self.params.n_estimators, self.params.max_depth, self.params.learning_rate)?;
+                info!("Trained gbdt ensemble ({} trees): trained at {}", m.trees.len(), Utc::now());
+                ModelKind::Gbdt(m)
+            }
+        };
+        self.model = Some(model);
         Ok(())
     }
 
-    /// Given a trajectory, compute score (continuation value). If weights not present, return error.
+    /// Given a trajectory, compute score (continuation value). If the model isn't trained, return error.
     pub fn score(&self, traj: &Trajectory) -> Result<f64, SigError> {
-        let feat = compute_truncated_signature(traj, self.params.truncation)?;
-        let p = feat.len();
-        if Some(p) != self.weights.as_ref().map(|w| w.len()) {
-            return Err(SigError::BadInput("model not trained or feature dim mismatch".to_string()));
+        let feat = compute_features(traj, self.params.truncation, &self.params.feature_config)?;
+        let model = self.model.as_ref().ok_or_else(|| SigError::BadInput("model not trained".to_string()))?;
+        if let Some(expected) = model.feature_dim() {
+            if feat.len() != expected {
+                return Err(SigError::BadInput("model not trained or feature dim mismatch".to_string()));
+            }
         }
         let x = Array1::from(feat);
-        let w = self.weights.as_ref().ok_or_else(|| SigError::BadInput("weights missing".to_string()))?;
-        Ok(score_feature_vec(w, &x))
+        Ok(model.predict(&x))
     }
 
     /// Decision rule: stop if immediate reward >= continuation_score (or thresholded)
@@ -227,23 +697,119 @@ impl SignatureStopper {
         let cont = self.score(traj)?;
         Ok(immediate_reward >= cont - threshold)
     }
+
+    /// Loads the `{"model": ..., "params": ...}` JSON the `trainer` binary writes out (see
+    /// `src/bin/trainer.rs`), for a live trajectory of dimensionality `d`.
+    /// Validates the loaded weights against `compute_feature_dim(d, params.truncation,
+    /// &params.feature_config)` so a model trained on a different `d` or `FeatureConfig` is
+    /// rejected here rather than silently mis-scoring the live feed.
+    pub fn load_weights(json: &str, d: usize) -> Result<Self, SigError> {
+        #[derive(Deserialize)]
+        struct SavedWeights {
+            model: ModelKind,
+            params: SigParams,
+        }
+        let saved: SavedWeights =
+            serde_json::from_str(json).map_err(|e| SigError::BadInput(format!("invalid weights json: {}", e)))?;
+        let expected_dim = compute_feature_dim(d, saved.params.truncation, &saved.params.feature_config);
+        if let Some(got) = saved.model.feature_dim() {
+            if got != expected_dim {
+                return Err(SigError::BadInput(format!(
+                    "loaded weights have {} features but d={} truncation={} expects {}",
+                    got, d, saved.params.truncation, expected_dim
+                )));
+            }
+        }
+        let mut stopper = SignatureStopper::new(saved.params, expected_dim);
+        stopper.model = Some(saved.model);
+        Ok(stopper)
+    }
 }
 
-// Convenience: compute feature dimension given d and truncation
-pub fn compute_feature_dim(d: usize, trunc: usize) -> usize {
-    let mut dim = 0;
-    if trunc >= 1 {
-        dim += d;
+impl AnalyticUnit for SignatureStopper {
+    fn train(&mut self, samples: &[TrainingSample]) -> Result<(), SigError> {
+        SignatureStopper::train(self, samples)
     }
-    if trunc >= 2 {
-        dim += d * d;
+
+    fn score(&self, traj: &Trajectory) -> Result<f64, SigError> {
+        SignatureStopper::score(self, traj)
     }
-    if trunc >= 3 {
-        dim += d * d * d;
+
+    fn should_stop(&self, traj: &Trajectory, immediate_reward: f64, threshold: f64) -> Result<bool, SigError> {
+        SignatureStopper::should_stop(self, traj, immediate_reward, threshold)
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+// Convenience: compute feature dimension given d, truncation and the feature config
+pub fn compute_feature_dim(d: usize, trunc: usize, config: &FeatureConfig) -> usize {
+    let mut dim = 0;
+    if config.use_signature {
+        if trunc >= 1 {
+            dim += d;
+        }
+        if trunc >= 2 {
+            dim += d * d;
+        }
+        if trunc >= 3 {
+            dim += d * d * d;
+        }
+    }
+    if config.use_spectral {
+        dim += d * config.spectral_bins;
     }
     dim
 }
 
+/// A stop/continue decision emitted by [`OnlineStopper::evaluate`] for one live observation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopSignal {
+    Stop,
+    Continue,
+}
+
+/// Drives a trained [`SignatureStopper`] off a live series of observations instead of a
+/// pre-recorded `Trajectory`: each call to `evaluate` appends one point to a bounded rolling
+/// window and rescores the trained model on `φ(path_so_far)` (`⟨weights, φ⟩` for the ridge
+/// backend, the tree ensemble's prediction for gbdt), so a long-running feed doesn't
+/// grow the trajectory (and the O(n^3) level-3 signature sums over it) without bound.
+pub struct OnlineStopper {
+    stopper: SignatureStopper,
+    window: std::collections::VecDeque<Vec<f64>>,
+    max_window: usize,
+}
+
+impl OnlineStopper {
+    pub fn new(stopper: SignatureStopper, max_window: usize) -> Self {
+        OnlineStopper { stopper, window: std::collections::VecDeque::with_capacity(max_window.min(1024)), max_window }
+    }
+
+    /// Appends `point` to the rolling window (dropping the oldest point once `max_window` is
+    /// exceeded) and scores the resulting path. Degrades gracefully to `Continue` - rather than
+    /// the `BadInput` error `compute_truncated_signature` would return - while the window is
+    /// still too short to form a single increment (fewer than 2 points), so a cold start never
+    /// panics on `traj[0]` or surfaces a spurious stop.
+    pub fn evaluate(&mut self, point: Vec<f64>, immediate_reward: f64, threshold: f64) -> StopSignal {
+        if self.window.len() == self.max_window {
+            self.window.pop_front();
+        }
+        self.window.push_back(point);
+
+        if self.window.len() < 2 {
+            return StopSignal::Continue;
+        }
+
+        let traj: Trajectory = self.window.iter().cloned().collect();
+        match self.stopper.should_stop(&traj, immediate_reward, threshold) {
+            Ok(true) => StopSignal::Stop,
+            Ok(false) | Err(_) => StopSignal::Continue,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -252,15 +818,21 @@ mod tests {
     fn test_trunc_signature_dim() {
         let d = 2;
         let trunc = 3;
-        let dim = compute_feature_dim(d, trunc);
+        let dim = compute_feature_dim(d, trunc, &FeatureConfig::default());
         assert_eq!(dim, 2 + 4 + 8);
     }
 
+    #[test]
+    fn test_spectral_feature_dim() {
+        let config = FeatureConfig { use_signature: false, use_spectral: true, spectral_window: 64, spectral_bins: 5 };
+        assert_eq!(compute_feature_dim(3, 3, &config), 3 * 5);
+    }
+
     #[test]
     fn test_feature_and_train() {
         // synthetic dataset: 1D deterministic increasing sequences with reward equal to last value
-        let params = SigParams { truncation: 2, ridge: 1e-3 };
-        let feature_dim = compute_feature_dim(1, params.truncation);
+        let params = SigParams { truncation: 2, ridge: 1e-3, ..SigParams::default() };
+        let feature_dim = compute_feature_dim(1, params.truncation, &params.feature_config);
         let mut stopper = SignatureStopper::new(params, feature_dim);
 
         let mut samples: Vec<TrainingSample> = Vec::new();
@@ -281,4 +853,152 @@ mod tests {
         let s = stopper.score(&test_traj).unwrap();
         assert!(s.is_finite());
     }
+
+    #[test]
+    fn test_gbdt_backend_trains_and_scores() {
+        // same synthetic dataset, but fit via the gbdt backend
+        let params = SigParams {
+            truncation: 2,
+            backend: RegressorBackend::Gbdt,
+            n_estimators: 10,
+            max_depth: 2,
+            learning_rate: 0.3,
+            ..SigParams::default()
+        };
+        let feature_dim = compute_feature_dim(1, params.truncation, &params.feature_config);
+        let mut stopper = SignatureStopper::new(params, feature_dim);
+
+        let mut samples: Vec<TrainingSample> = Vec::new();
+        for _ in 0..50 {
+            let mut traj: Trajectory = Vec::new();
+            let mut x = 0.0;
+            traj.push(vec![x]);
+            for _ in 0..5 {
+                x += 1.0;
+                traj.push(vec![x]);
+            }
+            samples.push(TrainingSample { traj, reward: x });
+        }
+
+        stopper.train(&samples).unwrap();
+        assert!(matches!(stopper.model, Some(ModelKind::Gbdt(_))));
+        let test_traj = samples[0].traj.clone();
+        let s = stopper.score(&test_traj).unwrap();
+        assert!(s.is_finite());
+    }
+
+    #[test]
+    fn test_spectral_features_on_oscillation() {
+        // a clean sinusoid should concentrate energy in a single low-frequency bin
+        let window = 64;
+        let mut traj: Trajectory = Vec::with_capacity(window + 1);
+        for i in 0..=window {
+            let t = i as f64;
+            traj.push(vec![(t * std::f64::consts::PI / 8.0).sin()]);
+        }
+        let feat = compute_spectral_features(&traj, window, 8).unwrap();
+        assert_eq!(feat.len(), 8);
+        assert!(feat.iter().all(|v| v.is_finite()));
+        let peak = feat.iter().cloned().fold(f64::MIN, f64::max);
+        assert!(peak > 0.0);
+        // a 16-sample period over a 64-sample window puts that energy in bin 64 / 16 = 4
+        assert_eq!(feat.iter().position(|v| *v == peak), Some(4));
+    }
+
+    #[test]
+    fn test_threshold_unit_stops_on_trend() {
+        let mut unit = ThresholdUnit::new(3, 0.5);
+        unit.train(&[]).unwrap(); // stateless: training is a no-op
+        let traj: Trajectory = vec![vec![0.0], vec![1.0], vec![2.0], vec![3.0]];
+        assert!(unit.should_stop(&traj, 0.0, 0.0).unwrap());
+
+        let flat: Trajectory = vec![vec![1.0], vec![1.0], vec![1.0], vec![1.0]];
+        assert!(!unit.should_stop(&flat, 0.0, 0.0).unwrap());
+    }
+
+    #[test]
+    fn test_pattern_unit_scores_nearer_pattern_higher() {
+        let mut unit = PatternUnit::new();
+        let samples = vec![
+            TrainingSample { traj: vec![vec![0.0], vec![1.0]], reward: 1.0 }, // pattern
+            TrainingSample { traj: vec![vec![0.0], vec![-1.0]], reward: -1.0 }, // anti-pattern
+        ];
+        unit.train(&samples).unwrap();
+        let near_pattern: Trajectory = vec![vec![0.0], vec![0.9]];
+        let near_anti: Trajectory = vec![vec![0.0], vec![-0.9]];
+        assert!(unit.score(&near_pattern).unwrap() > unit.score(&near_anti).unwrap());
+    }
+
+    #[test]
+    fn test_load_weights_round_trips_trained_model() {
+        let params = SigParams { truncation: 2, ..SigParams::default() };
+        let feature_dim = compute_feature_dim(1, params.truncation, &params.feature_config);
+        let mut stopper = SignatureStopper::new(params.clone(), feature_dim);
+        let samples: Vec<TrainingSample> =
+            (0..10).map(|i| TrainingSample { traj: vec![vec![0.0], vec![i as f64]], reward: i as f64 }).collect();
+        stopper.train(&samples).unwrap();
+
+        let json = serde_json::to_string(&serde_json::json!({ "model": stopper.model, "params": params })).unwrap();
+        let loaded = SignatureStopper::load_weights(&json, 1).unwrap();
+        let traj = samples[0].traj.clone();
+        assert_eq!(loaded.score(&traj).unwrap(), stopper.score(&traj).unwrap());
+    }
+
+    #[test]
+    fn test_load_weights_rejects_dimension_mismatch() {
+        let params = SigParams { truncation: 2, ..SigParams::default() };
+        let feature_dim = compute_feature_dim(1, params.truncation, &params.feature_config);
+        let mut stopper = SignatureStopper::new(params.clone(), feature_dim);
+        let samples: Vec<TrainingSample> =
+            (0..10).map(|i| TrainingSample { traj: vec![vec![0.0], vec![i as f64]], reward: i as f64 }).collect();
+        stopper.train(&samples).unwrap();
+
+        let json = serde_json::to_string(&serde_json::json!({ "model": stopper.model, "params": params })).unwrap();
+        // d=2 expects a different feature_dim than the weights were trained with (d=1)
+        assert!(SignatureStopper::load_weights(&json, 2).is_err());
+    }
+
+    #[test]
+    fn test_online_stopper_continues_on_short_window() {
+        let params = SigParams { truncation: 2, ..SigParams::default() };
+        let feature_dim = compute_feature_dim(1, params.truncation, &params.feature_config);
+        let mut stopper = SignatureStopper::new(params, feature_dim);
+        let samples: Vec<TrainingSample> =
+            (0..10).map(|i| TrainingSample { traj: vec![vec![0.0], vec![i as f64]], reward: i as f64 }).collect();
+        stopper.train(&samples).unwrap();
+
+        let mut online = OnlineStopper::new(stopper, 5);
+        assert_eq!(online.evaluate(vec![0.0], 0.0, 0.0), StopSignal::Continue);
+    }
+
+    #[test]
+    fn test_online_stopper_evicts_oldest_past_max_window() {
+        let params = SigParams { truncation: 1, ..SigParams::default() };
+        let feature_dim = compute_feature_dim(1, params.truncation, &params.feature_config);
+        let mut stopper = SignatureStopper::new(params, feature_dim);
+        let samples: Vec<TrainingSample> =
+            (0..10).map(|i| TrainingSample { traj: vec![vec![0.0], vec![i as f64]], reward: i as f64 }).collect();
+        stopper.train(&samples).unwrap();
+
+        let mut online = OnlineStopper::new(stopper, 3);
+        for i in 0..10 {
+            online.evaluate(vec![i as f64], 0.0, 0.0);
+        }
+        assert_eq!(online.window.len(), 3);
+    }
+
+    #[test]
+    fn test_analytic_unit_config_builds_each_unit() {
+        let configs = vec![
+            AnalyticUnitConfig::Signature { params: SigParams::default(), feature_dim: compute_feature_dim(1, 3, &FeatureConfig::default()) },
+            AnalyticUnitConfig::Threshold { window: 3, threshold: 0.1 },
+            AnalyticUnitConfig::Pattern,
+        ];
+        for config in configs {
+            let unit = config.build();
+            assert!(unit.as_any().downcast_ref::<SignatureStopper>().is_some()
+                || unit.as_any().downcast_ref::<ThresholdUnit>().is_some()
+                || unit.as_any().downcast_ref::<PatternUnit>().is_some());
+        }
+    }
 }
@@ -1,8 +1,10 @@
 use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
 use pyo3::types::{PyDict, PyList};
-use serde::{Deserialize, Serialize};
-use signature_optimal_stopping::{compute_feature_dim, SigParams, SignatureStopper, TrainingSample, Trajectory};
+use signature_optimal_stopping::{
+    compute_feature_dim, AnalyticUnit, AnalyticUnitConfig, FeatureConfig, ModelKind, RegressorBackend, SigParams,
+    SignatureStopper, TrainingSample, Trajectory,
+};
 use std::sync::Mutex;
 use std::sync::Arc;
 use thiserror::Error;
@@ -22,89 +24,158 @@ impl From<PySigError> for PyErr {
     }
 }
 
-/// Minimal serde-friendly structs for JSON interop
-#[derive(Serialize, Deserialize)]
-struct PyTrainingSample {
-    traj: Vec<Vec<f64>>,
-    reward: f64,
+fn parse_trajectory(json_str: &str) -> Result<Trajectory, PySigError> {
+    let v: serde_json::Value = serde_json::from_str(json_str).map_err(|e| PySigError::InvalidInput(format!("invalid traj json: {}", e)))?;
+    let traj_v = v.as_array().ok_or_else(|| PySigError::InvalidInput("trajectory must be a JSON array".to_string()))?;
+    let mut traj: Trajectory = Vec::with_capacity(traj_v.len());
+    for point in traj_v.iter() {
+        let pt = point.as_array().ok_or_else(|| PySigError::InvalidInput("trajectory point must be array".to_string()))?;
+        let mut row: Vec<f64> = Vec::with_capacity(pt.len());
+        for val in pt.iter() {
+            let num = val.as_f64().ok_or_else(|| PySigError::InvalidInput("trajectory point contains non-number".to_string()))?;
+            row.push(num);
+        }
+        traj.push(row);
+    }
+    Ok(traj)
+}
+
+fn parse_training_samples(samples_v: &[serde_json::Value]) -> Result<Vec<TrainingSample>, PySigError> {
+    let mut samples: Vec<TrainingSample> = Vec::with_capacity(samples_v.len());
+    for s in samples_v.iter() {
+        let traj_v = s.get("traj").and_then(|t| t.as_array()).ok_or_else(|| PySigError::InvalidInput("each sample.traj must be an array".to_string()))?;
+        let mut traj: Trajectory = Vec::with_capacity(traj_v.len());
+        for point in traj_v.iter() {
+            let pt = point.as_array().ok_or_else(|| PySigError::InvalidInput("trajectory points must be arrays of numbers".to_string()))?;
+            let mut row: Vec<f64> = Vec::with_capacity(pt.len());
+            for val in pt.iter() {
+                let num = val.as_f64().ok_or_else(|| PySigError::InvalidInput("trajectory point contains non-number".to_string()))?;
+                row.push(num);
+            }
+            traj.push(row);
+        }
+        let reward = s.get("reward").and_then(|r| r.as_f64()).ok_or_else(|| PySigError::InvalidInput("sample.reward must be a number".to_string()))?;
+        samples.push(TrainingSample { traj, reward });
+    }
+    Ok(samples)
 }
 
+/// Builds the `AnalyticUnitConfig` selected by a params JSON dict via its `"unit"` field
+/// ("signature" (default), "threshold", or "pattern"), falling back to `default_unit` when a
+/// field is absent so repeated `train_from_json` calls can omit unchanged params.
+fn unit_config_from_params(params: &serde_json::Value, default_unit: &AnalyticUnitConfig, samples: &[TrainingSample]) -> Result<AnalyticUnitConfig, PySigError> {
+    let default_kind = match default_unit {
+        AnalyticUnitConfig::Signature { .. } => "signature",
+        AnalyticUnitConfig::Threshold { .. } => "threshold",
+        AnalyticUnitConfig::Pattern => "pattern",
+    };
+    let unit = params.get("unit").and_then(|u| u.as_str()).unwrap_or(default_kind);
+    match unit {
+        "threshold" => {
+            let (default_window, default_threshold) = match default_unit {
+                AnalyticUnitConfig::Threshold { window, threshold } => (*window, *threshold),
+                _ => (10, 0.0),
+            };
+            let window = params.get("window").and_then(|w| w.as_u64()).map(|u| u as usize).unwrap_or(default_window);
+            let threshold = params.get("threshold").and_then(|t| t.as_f64()).unwrap_or(default_threshold);
+            Ok(AnalyticUnitConfig::Threshold { window, threshold })
+        }
+        "pattern" => Ok(AnalyticUnitConfig::Pattern),
+        "signature" => {
+            let default_params = match default_unit {
+                AnalyticUnitConfig::Signature { params, .. } => params.clone(),
+                _ => SigParams::default(),
+            };
+            let trunc = params.get("truncation").and_then(|t| t.as_u64()).map(|u| u as usize).unwrap_or(default_params.truncation);
+            let ridge = params.get("ridge").and_then(|r| r.as_f64()).unwrap_or(default_params.ridge);
+            let backend = match params.get("backend").and_then(|b| b.as_str()) {
+                Some("gbdt") => RegressorBackend::Gbdt,
+                Some("ridge") => RegressorBackend::Ridge,
+                _ => default_params.backend,
+            };
+            let n_estimators = params.get("n_estimators").and_then(|n| n.as_u64()).map(|u| u as usize).unwrap_or(default_params.n_estimators);
+            let max_depth = params.get("max_depth").and_then(|d| d.as_u64()).map(|u| u as usize).unwrap_or(default_params.max_depth);
+            let learning_rate = params.get("learning_rate").and_then(|r| r.as_f64()).unwrap_or(default_params.learning_rate);
+            let feature_config: FeatureConfig = match params.get("feature_config") {
+                Some(fc) => serde_json::from_value(fc.clone()).map_err(|e| PySigError::InvalidInput(format!("invalid feature_config: {}", e)))?,
+                None => default_params.feature_config.clone(),
+            };
+            let sig_params = SigParams { truncation: trunc, ridge, backend, n_estimators, max_depth, learning_rate, feature_config };
+            let d = samples.get(0).and_then(|s| s.traj.get(0)).map(|r| r.len()).ok_or_else(|| PySigError::InvalidInput("no sample/traj provided to infer feature dimension".to_string()))?;
+            let feature_dim = compute_feature_dim(d, trunc, &sig_params.feature_config);
+            Ok(AnalyticUnitConfig::Signature { params: sig_params, feature_dim })
+        }
+        other => Err(PySigError::InvalidInput(format!("unknown unit \"{}\"", other))),
+    }
+}
+
+/// Python wrapper around any `AnalyticUnit`: the signature-based regression stopper, the
+/// stateless threshold rule, or the nearest-pattern matcher, selectable by name.
 #[pyclass]
 struct PySignatureStopper {
-    inner: Arc<Mutex<SignatureStopper>>,
-    params: SigParams,
+    inner: Arc<Mutex<Box<dyn AnalyticUnit + Send>>>,
+    default_unit: Mutex<AnalyticUnitConfig>,
 }
 
 #[pymethods]
 impl PySignatureStopper {
     /// new(truncation: int = 2, ridge: float = 1e-3, dim_hint: Optional[int] = None)
-    /// Create a new Python wrapper. If dim_hint is None, the wrapper will infer feature dimension from first sample at train time.
+    /// Create a new Python wrapper configured with the signature analytic unit (ridge backend
+    /// by default). Use `train_from_json`'s `"unit"` param to switch to "threshold"/"pattern".
+    /// If dim_hint is None, the wrapper will infer feature dimension from first sample at train time.
     #[new]
     fn new(truncation: Option<usize>, ridge: Option<f64>, dim_hint: Option<usize>) -> Self {
         env_logger::init();
         let trunc = truncation.unwrap_or(2);
         let ridge = ridge.unwrap_or(1e-3);
-        let params = SigParams { truncation: trunc, ridge };
+        let params = SigParams { truncation: trunc, ridge, ..SigParams::default() };
         let feature_dim = dim_hint.unwrap_or(0);
-        let stopper = SignatureStopper::new(params.clone(), feature_dim);
+        let config = AnalyticUnitConfig::Signature { params, feature_dim };
         PySignatureStopper {
-            inner: Arc::new(Mutex::new(stopper)),
-            params,
+            inner: Arc::new(Mutex::new(config.build())),
+            default_unit: Mutex::new(config),
         }
     }
 
     /// train_from_json(json_str: str) -> dict
-    /// Expects JSON with structure: { "params": {"truncation": <int>, "ridge": <float>}, "samples": [{"traj": [[...],[...]], "reward": <float>}, ...] }
-    /// Returns a dict { "weights": [...], "params": {...} }
+    /// Expects JSON with structure: { "params": {"unit": "signature"|"threshold"|"pattern", ...}, "samples": [{"traj": [[...],[...]], "reward": <float>}, ...] }
+    /// Returns a dict with "weights": [...] (signature/ridge only) or "model": <json> (signature/gbdt),
+    /// plus "params": <json> (the serialized unit config) for every unit; threshold/pattern get only "params".
     fn train_from_json(&self, json_str: &str) -> PyResult<PyObject> {
         let py = unsafe { Python::assume_gil_acquired() };
-        // parse JSON
         let v: serde_json::Value = serde_json::from_str(json_str).map_err(|e| PySigError::InvalidInput(format!("invalid json: {}", e)))?;
         let params = v.get("params").cloned().unwrap_or(serde_json::json!({}));
-        let trunc = params.get("truncation").and_then(|t| t.as_u64()).map(|u| u as usize).unwrap_or(self.params.truncation);
-        let ridge = params.get("ridge").and_then(|r| r.as_f64()).unwrap_or(self.params.ridge);
         let samples_v = v.get("samples").and_then(|s| s.as_array()).ok_or_else(|| PySigError::InvalidInput("samples must be an array".to_string()))?;
+        let samples = parse_training_samples(samples_v)?;
+
+        let default_unit = self.default_unit.lock().map_err(|_| PySigError::Internal("mutex poisoned".to_string()))?.clone();
+        let config = unit_config_from_params(&params, &default_unit, &samples)?;
+        let mut unit = config.build();
+        unit.train(&samples).map_err(|e| PySigError::Internal(format!("training failed: {}", e)))?;
 
-        let mut samples: Vec<TrainingSample> = Vec::with_capacity(samples_v.len());
-        for s in samples_v.iter() {
-            let traj_v = s.get("traj").and_then(|t| t.as_array()).ok_or_else(|| PySigError::InvalidInput("each sample.traj must be an array".to_string()))?;
-            let mut traj: Trajectory = Vec::with_capacity(traj_v.len());
-            for point in traj_v.iter() {
-                let pt = point.as_array().ok_or_else(|| PySigError::InvalidInput("trajectory points must be arrays of numbers".to_string()))?;
-                let mut row: Vec<f64> = Vec::with_capacity(pt.len());
-                for val in pt.iter() {
-                    let num = val.as_f64().ok_or_else(|| PySigError::InvalidInput("trajectory point contains non-number".to_string()))?;
-                    row.push(num);
+        let out = PyDict::new(py);
+        if let Some(stopper) = unit.as_any().downcast_ref::<SignatureStopper>() {
+            match stopper.model.as_ref() {
+                Some(ModelKind::Ridge(w)) => {
+                    out.set_item("weights", PyList::new(py, w.iter().cloned()))?;
+                }
+                Some(ModelKind::Gbdt(m)) => {
+                    out.set_item("model", serde_json::to_string(m).map_err(|e| PySigError::Internal(format!("model serialize: {}", e)))?)?;
                 }
-                traj.push(row);
+                None => {}
             }
-            let reward = s.get("reward").and_then(|r| r.as_f64()).ok_or_else(|| PySigError::InvalidInput("sample.reward must be a number".to_string()))?;
-            samples.push(TrainingSample { traj, reward });
         }
 
-        // infer feature dim if needed
-        let d = samples.get(0).and_then(|s| s.traj.get(0)).map(|r| r.len()).ok_or_else(|| PySigError::InvalidInput("no sample/traj provided to infer feature dimension".to_string()))?;
-        let feature_dim = compute_feature_dim(d, trunc);
-
-        // create a local stopper and train (or replace inner)
-        let mut stopper = SignatureStopper::new(SigParams { truncation: trunc, ridge }, feature_dim);
-        stopper.train(&samples).map_err(|e| PySigError::Internal(format!("training failed: {}", e)))?;
-
-        // store weights into inner
         let mut guard = self.inner.lock().map_err(|_| PySigError::Internal("mutex poisoned".to_string()))?;
-        *guard = stopper;
+        *guard = unit;
+        *self.default_unit.lock().map_err(|_| PySigError::Internal("mutex poisoned".to_string()))? = config.clone();
 
-        // prepare return dict
-        let weights = guard.weights.as_ref().ok_or_else(|| PySigError::Internal("weights missing after training".to_string()))?;
-        let py_weights = PyList::new(py, weights.iter().cloned());
-        let out = PyDict::new(py);
-        out.set_item("weights", py_weights)?;
-        out.set_item("params", serde_json::json!({"truncation": trunc, "ridge": ridge}).to_string())?;
+        out.set_item("params", serde_json::to_string(&config).map_err(|e| PySigError::Internal(format!("params serialize: {}", e)))?)?;
         Ok(out.to_object(py))
     }
 
     /// train(samples_json: str) -> None
-    /// Alias to train_from_json but returns None (keeps weights inside object)
+    /// Alias to train_from_json but returns None (keeps trained state inside object)
     fn train(&self, samples_json: &str) -> PyResult<()> {
         let _ = self.train_from_json(samples_json)?;
         Ok(())
@@ -113,18 +184,7 @@ impl PySignatureStopper {
     /// score(traj_json: str) -> float
     /// traj_json: JSON array of arrays representing the trajectory [[x1,...],[x2,...],...]
     fn score(&self, traj_json: &str) -> PyResult<f64> {
-        let v: serde_json::Value = serde_json::from_str(traj_json).map_err(|e| PySigError::InvalidInput(format!("invalid traj json: {}", e)))?;
-        let traj_v = v.as_array().ok_or_else(|| PySigError::InvalidInput("trajectory must be a JSON array".to_string()))?;
-        let mut traj: Trajectory = Vec::with_capacity(traj_v.len());
-        for point in traj_v.iter() {
-            let pt = point.as_array().ok_or_else(|| PySigError::InvalidInput("trajectory point must be array".to_string()))?;
-            let mut row: Vec<f64> = Vec::with_capacity(pt.len());
-            for val in pt.iter() {
-                let num = val.as_f64().ok_or_else(|| PySigError::InvalidInput("trajectory point contains non-number".to_string()))?;
-                row.push(num);
-            }
-            traj.push(row);
-        }
+        let traj = parse_trajectory(traj_json)?;
         let guard = self.inner.lock().map_err(|_| PySigError::Internal("mutex poisoned".to_string()))?;
         let sc = guard.score(&traj).map_err(|e| PySigError::Internal(format!("score error: {}", e)))?;
         Ok(sc)
@@ -133,39 +193,31 @@ impl PySignatureStopper {
     /// should_stop(traj_json: str, immediate_reward: float, threshold: float = 0.0) -> bool
     fn should_stop(&self, traj_json: &str, immediate_reward: f64, threshold: Option<f64>) -> PyResult<bool> {
         let thr = threshold.unwrap_or(0.0);
-        let v: serde_json::Value = serde_json::from_str(traj_json).map_err(|e| PySigError::InvalidInput(format!("invalid traj json: {}", e)))?;
-        let traj_v = v.as_array().ok_or_else(|| PySigError::InvalidInput("trajectory must be a JSON array".to_string()))?;
-        let mut traj: Trajectory = Vec::with_capacity(traj_v.len());
-        for point in traj_v.iter() {
-            let pt = point.as_array().ok_or_else(|| PySigError::InvalidInput("trajectory point must be array".to_string()))?;
-            let mut row: Vec<f64> = Vec::with_capacity(pt.len());
-            for val in pt.iter() {
-                let num = val.as_f64().ok_or_else(|| PySigError::InvalidInput("trajectory point contains non-number".to_string()))?;
-                row.push(num);
-            }
-            traj.push(row);
-        }
+        let traj = parse_trajectory(traj_json)?;
         let guard = self.inner.lock().map_err(|_| PySigError::Internal("mutex poisoned".to_string()))?;
         let res = guard.should_stop(&traj, immediate_reward, thr).map_err(|e| PySigError::Internal(format!("should_stop error: {}", e)))?;
         Ok(res)
     }
 
     /// get_weights() -> list[float] or None
+    /// Only meaningful for the signature unit's ridge backend; returns None for gbdt, the
+    /// threshold/pattern units, or an untrained model.
     fn get_weights(&self) -> PyResult<Option<PyObject>> {
         let py = unsafe { Python::assume_gil_acquired() };
         let guard = self.inner.lock().map_err(|_| PySigError::Internal("mutex poisoned".to_string()))?;
-        if let Some(w) = &guard.weights {
-            let list = PyList::new(py, w.iter().cloned());
-            Ok(Some(list.to_object(py)))
-        } else {
-            Ok(None)
+        match guard.as_any().downcast_ref::<SignatureStopper>().and_then(|s| s.model.as_ref()) {
+            Some(ModelKind::Ridge(w)) => {
+                let list = PyList::new(py, w.iter().cloned());
+                Ok(Some(list.to_object(py)))
+            }
+            _ => Ok(None),
         }
     }
 
     /// compute_feature_dim(d: int, trunc: int) -> int (staticmethod)
     #[staticmethod]
     fn compute_feature_dim_py(d: usize, trunc: usize) -> PyResult<usize> {
-        Ok(compute_feature_dim(d, trunc))
+        Ok(compute_feature_dim(d, trunc, &FeatureConfig::default()))
     }
 }
 
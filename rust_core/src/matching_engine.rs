@@ -1,4 +1,4 @@
-use crate::orderbook::{OrderBook, Price, Qty};
+use crate::orderbook::{Decimal, OrderBook, Price, Qty};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Side { Buy, Sell }
@@ -11,24 +11,24 @@ pub struct Fill {
 }
 
 pub fn execute_market(book: &mut OrderBook, side: Side, mut qty: Qty) -> (Qty, f64, Vec<Fill>) {
-    let mut filled=0.0; let mut cost=0.0; let mut fills=Vec::new();
+    let mut filled=Decimal::ZERO; let mut cost=0.0; let mut fills=Vec::new();
     match side {
         Side::Buy => {
-            let mut it: Vec<Price> = book.asks.price_iter().collect();
+            let it: Vec<Price> = book.asks.price_iter().collect();
             for p in it {
-                if qty<=0.0 { break; }
+                if !qty.is_positive() { break; }
                 let (f, c, parts) = book.asks.consume_at_price(p, qty);
-                for (_id, q, pr) in parts { fills.push(Fill{price:pr, qty:q, cost:q*pr}); }
-                filled += f; cost += c; qty -= f;
+                for (_id, q, pr) in parts { fills.push(Fill{price:pr, qty:q, cost:q.to_f64()*pr.to_f64()}); }
+                filled = filled.saturating_add(f); cost += c; qty = qty.saturating_sub(f);
             }
         }
         Side::Sell => {
-            let mut it: Vec<Price> = book.bids.price_iter().collect();
+            let it: Vec<Price> = book.bids.price_iter().collect();
             for p in it {
-                if qty<=0.0 { break; }
+                if !qty.is_positive() { break; }
                 let (f, c, parts) = book.bids.consume_at_price(p, qty);
-                for (_id, q, pr) in parts { fills.push(Fill{price:pr, qty:q, cost:q*pr}); }
-                filled += f; cost += c; qty -= f;
+                for (_id, q, pr) in parts { fills.push(Fill{price:pr, qty:q, cost:q.to_f64()*pr.to_f64()}); }
+                filled = filled.saturating_add(f); cost += c; qty = qty.saturating_sub(f);
             }
         }
     }
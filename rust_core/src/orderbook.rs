@@ -1,8 +1,143 @@
-use serde::{Serialize, Deserialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::collections::{BTreeMap, VecDeque};
 
-pub type Price = f64;
-pub type Qty = f64;
+/// Number of decimal digits a [`Decimal`] keeps exactly (i.e. the value is `raw / 10^SCALE`).
+pub const DECIMAL_SCALE: u32 = 8;
+
+/// A scaled-integer fixed-point number, exact under `Ord`/`Eq` unlike `f64`. Used for book
+/// prices and quantities so `BTreeMap` level keys don't suffer `f64`'s non-deterministic
+/// ordering and arithmetic like `consume_at_price`'s running totals doesn't drift.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Decimal(i64);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecimalError {
+    Invalid(String),
+    TooPrecise(String),
+}
+
+impl std::fmt::Display for DecimalError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DecimalError::Invalid(s) => write!(f, "invalid decimal: {}", s),
+            DecimalError::TooPrecise(s) => write!(f, "more than {} fractional digits: {}", DECIMAL_SCALE, s),
+        }
+    }
+}
+
+impl Decimal {
+    pub const ZERO: Decimal = Decimal(0);
+
+    fn scale() -> i64 {
+        10i64.pow(DECIMAL_SCALE)
+    }
+
+    pub fn from_raw(raw: i64) -> Self {
+        Decimal(raw)
+    }
+
+    pub fn raw(self) -> i64 {
+        self.0
+    }
+
+    /// Parses a decimal string (e.g. `"0.00012"`, `"-3"`) into its exact scaled form.
+    pub fn from_str_scaled(s: &str) -> Result<Self, DecimalError> {
+        let s = s.trim();
+        let (neg, s) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s),
+        };
+        let (int_part, frac_part) = s.split_once('.').unwrap_or((s, ""));
+        if frac_part.len() > DECIMAL_SCALE as usize {
+            return Err(DecimalError::TooPrecise(s.to_string()));
+        }
+        let int_val: i64 = if int_part.is_empty() { 0 } else { int_part.parse().map_err(|_| DecimalError::Invalid(s.to_string()))? };
+        let mut frac_digits = frac_part.to_string();
+        while frac_digits.len() < DECIMAL_SCALE as usize {
+            frac_digits.push('0');
+        }
+        let frac_val: i64 = if frac_digits.is_empty() { 0 } else { frac_digits.parse().map_err(|_| DecimalError::Invalid(s.to_string()))? };
+        let raw = int_val * Self::scale() + frac_val;
+        Ok(Decimal(if neg { -raw } else { raw }))
+    }
+
+    /// Builds a `Decimal` from an `f64`, rounding to `DECIMAL_SCALE` digits. Lossy for values
+    /// that don't already fit the scale; prefer `from_str_scaled` when parsing venue payloads.
+    pub fn from_f64(v: f64) -> Self {
+        Decimal((v * Self::scale() as f64).round() as i64)
+    }
+
+    pub fn to_f64(self) -> f64 {
+        self.0 as f64 / Self::scale() as f64
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn is_positive(self) -> bool {
+        self.0 > 0
+    }
+
+    pub fn saturating_add(self, other: Decimal) -> Decimal {
+        Decimal(self.0.saturating_add(other.0))
+    }
+
+    pub fn saturating_sub(self, other: Decimal) -> Decimal {
+        Decimal(self.0.saturating_sub(other.0))
+    }
+
+    pub fn min(self, other: Decimal) -> Decimal {
+        if self.0 <= other.0 {
+            self
+        } else {
+            other
+        }
+    }
+}
+
+impl std::str::FromStr for Decimal {
+    type Err = DecimalError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Decimal::from_str_scaled(s)
+    }
+}
+
+impl Serialize for Decimal {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("{:.*}", DECIMAL_SCALE as usize, self.to_f64()))
+    }
+}
+
+impl<'de> Deserialize<'de> for Decimal {
+    /// Accepts both JSON strings (`"0.00012"`, exact) and JSON numbers (rounded to scale), so
+    /// exchange connectors can parse venue payloads whichever way they arrive.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct DecimalVisitor;
+        impl<'de> serde::de::Visitor<'de> for DecimalVisitor {
+            type Value = Decimal;
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("a decimal string or number")
+            }
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Decimal, E> {
+                Decimal::from_str_scaled(v).map_err(|e| E::custom(e.to_string()))
+            }
+            fn visit_f64<E: serde::de::Error>(self, v: f64) -> Result<Decimal, E> {
+                Ok(Decimal::from_f64(v))
+            }
+            fn visit_i64<E: serde::de::Error>(self, v: i64) -> Result<Decimal, E> {
+                Ok(Decimal::from_f64(v as f64))
+            }
+            fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<Decimal, E> {
+                Ok(Decimal::from_f64(v as f64))
+            }
+        }
+        deserializer.deserialize_any(DecimalVisitor)
+    }
+}
+
+pub type Price = Decimal;
+pub type Qty = Decimal;
 pub type Ts = i64;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -32,29 +167,33 @@ impl OrderBookSide {
         }
     }
     pub fn total_qty(&self) -> Qty {
-        self.levels.values().map(|q| q.iter().map(|o| o.qty).sum::<Qty>()).sum()
+        self.levels.values().fold(Qty::ZERO, |acc, q| q.iter().fold(acc, |acc, o| acc.saturating_add(o.qty)))
     }
+    /// Sets a level's total quantity outright (overwriting whatever was resting there), matching
+    /// venue depth-update semantics: this type doesn't model genuinely distinct resting orders,
+    /// so a repeated update at the same price replaces the level rather than piling up entries.
     pub fn add_limit(&mut self, id: u64, price: Price, qty: Qty, ts: Ts) {
-        let q = self.levels.entry(price).or_insert_with(VecDeque::new);
+        let mut q = VecDeque::new();
         q.push_back(Order { id, price, qty, ts });
+        self.levels.insert(price, q);
     }
     pub fn consume_at_price(&mut self, price: Price, mut qty: Qty) -> (Qty, f64, Vec<(u64, Qty, Price)>) {
         // returns (filled_qty, cost, fills)
-        let mut filled = 0.0;
+        let mut filled = Qty::ZERO;
         let mut cost = 0.0;
         let mut fills = Vec::new();
         if let Some(queue) = self.levels.get_mut(&price) {
-            while qty > 0.0 {
-                if let Some(mut o) = queue.front().cloned() {
+            while qty.is_positive() {
+                if let Some(o) = queue.front().cloned() {
                     let take = Qty::min(o.qty, qty);
-                    filled += take;
-                    cost += take * price;
+                    filled = filled.saturating_add(take);
+                    cost += take.to_f64() * price.to_f64();
                     fills.push((o.id, take, price));
                     // mutate front
                     let front = queue.front_mut().unwrap();
-                    front.qty -= take;
-                    qty -= take;
-                    if front.qty <= 1e-12 {
+                    front.qty = front.qty.saturating_sub(take);
+                    qty = qty.saturating_sub(take);
+                    if front.qty.is_zero() {
                         queue.pop_front();
                     }
                 } else { break; }
@@ -88,29 +227,29 @@ impl OrderBook {
     pub fn best_ask(&self) -> Option<Price> { self.asks.best_price() }
     pub fn mid(&self) -> Option<Price> {
         match (self.best_bid(), self.best_ask()) {
-            (Some(b), Some(a)) => Some((a+b)/2.0),
+            (Some(b), Some(a)) => Some(Decimal::from_raw((a.raw() + b.raw()) / 2)),
             _ => None
         }
     }
-    pub fn spread(&self) -> Option<f64> {
+    pub fn spread(&self) -> Option<Decimal> {
         match (self.best_bid(), self.best_ask()) {
-            (Some(b), Some(a)) => Some(a-b),
+            (Some(b), Some(a)) => Some(a.saturating_sub(b)),
             _ => None
         }
     }
     pub fn apply_snapshot(&mut self, bids: &[(Price, Qty)], asks: &[(Price, Qty)], ts: Ts) {
         self.bids.levels.clear(); self.asks.levels.clear();
-        for (p,q) in bids { if *q>0.0 { self.bids.add_limit(self.next_id(), *p, *q, ts); } }
-        for (p,q) in asks { if *q>0.0 { self.asks.add_limit(self.next_id(), *p, *q, ts); } }
+        for (p,q) in bids { if q.is_positive() { self.bids.add_limit(self.next_id(), *p, *q, ts); } }
+        for (p,q) in asks { if q.is_positive() { self.asks.add_limit(self.next_id(), *p, *q, ts); } }
         self.ts = ts;
     }
     pub fn apply_delta(&mut self, bid_d: &[(Price, Qty)], ask_d: &[(Price, Qty)], ts: Ts) {
         for (p, q) in bid_d {
-            if *q <= 0.0 { self.bids.levels.remove(p); }
+            if !q.is_positive() { self.bids.levels.remove(p); }
             else { self.bids.add_limit(self.next_id(), *p, *q, ts); }
         }
         for (p, q) in ask_d {
-            if *q <= 0.0 { self.asks.levels.remove(p); }
+            if !q.is_positive() { self.asks.levels.remove(p); }
             else { self.asks.add_limit(self.next_id(), *p, *q, ts); }
         }
         self.ts = ts;
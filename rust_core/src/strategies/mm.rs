@@ -1,9 +1,19 @@
-pub struct MMQuote { pub bid_px: f64, pub ask_px: f64, pub bid_size: f64, pub ask_size: f64 }
-pub fn imbalance_quote(bids: &[(f64,f64)], asks: &[(f64,f64)], spread: f64, skew_coeff: f64) -> MMQuote {
-    let bid_vol: f64 = bids.iter().map(|(_,s)| *s).sum();
-    let ask_vol: f64 = asks.iter().map(|(_,s)| *s).sum();
-    let im = if (bid_vol + ask_vol) == 0.0 { 0.0 } else { (bid_vol - ask_vol)/(bid_vol + ask_vol) };
-    let mid = if !bids.is_empty() && !asks.is_empty() { (bids[0].0 + asks[0].0)/2.0 } else { 0.0 };
-    let half = spread/2.0; let bid_px = mid - half + im*skew_coeff; let ask_px = mid + half + im*skew_coeff;
-    MMQuote { bid_px, ask_px, bid_size: 1.0, ask_size: 1.0 }
+use crate::orderbook::Decimal;
+
+pub struct MMQuote { pub bid_px: Decimal, pub ask_px: Decimal, pub bid_size: Decimal, pub ask_size: Decimal }
+
+pub fn imbalance_quote(bids: &[(Decimal, Decimal)], asks: &[(Decimal, Decimal)], spread: Decimal, skew_coeff: f64) -> MMQuote {
+    let bid_vol: f64 = bids.iter().map(|(_, s)| s.to_f64()).sum();
+    let ask_vol: f64 = asks.iter().map(|(_, s)| s.to_f64()).sum();
+    let im = if (bid_vol + ask_vol) == 0.0 { 0.0 } else { (bid_vol - ask_vol) / (bid_vol + ask_vol) };
+    let mid = if !bids.is_empty() && !asks.is_empty() {
+        Decimal::from_raw((bids[0].0.raw() + asks[0].0.raw()) / 2)
+    } else {
+        Decimal::ZERO
+    };
+    let skew = Decimal::from_f64(im * skew_coeff);
+    let half = Decimal::from_raw(spread.raw() / 2);
+    let bid_px = mid.saturating_sub(half).saturating_add(skew);
+    let ask_px = mid.saturating_add(half).saturating_add(skew);
+    MMQuote { bid_px, ask_px, bid_size: Decimal::from_f64(1.0), ask_size: Decimal::from_f64(1.0) }
 }
@@ -1,19 +1,33 @@
 use tokio::sync::mpsc::{Sender, Receiver, channel};
 use crate::orderbook::OrderBook;
 use serde_json::Value;
+use signature_optimal_stopping::{OnlineStopper, StopSignal};
 pub type Event = Value;
-pub fn spawn_processor() -> (Sender<Event>, Receiver<OrderBook>) {
+
+/// Spawns the order-book state machine: takes snapshot/delta events from the returned sender and
+/// emits the `OrderBook` after each one on the returned receiver, paired with a `StopSignal` from
+/// `stopper` scored against the book's mid price (`Continue` when the book has no mid). Only an
+/// event's `type` and `ts` are read: a snapshot clears the book and a delta changes no levels, so
+/// no price levels from the event are applied and the book stays empty. `stopper` is optional;
+/// pass `None` to run the book state machine without scoring, which always yields
+/// `StopSignal::Continue`.
+pub fn spawn_processor(mut stopper: Option<OnlineStopper>) -> (Sender<Event>, Receiver<(OrderBook, StopSignal)>) {
     let (in_tx, mut in_rx) = channel::<Event>(1024);
-    let (out_tx, out_rx) = channel::<OrderBook>(64);
+    let (out_tx, out_rx) = channel::<(OrderBook, StopSignal)>(64);
     tokio::spawn(async move {
         let mut state = OrderBook::new();
         while let Some(ev) = in_rx.recv().await {
+            let ts = ev["ts"].as_i64().unwrap_or(0);
             if ev.get("type") == Some(&Value::String("snapshot".into())) {
-                state = state.with_snapshot(vec![], vec![], ev["ts"].as_i64().unwrap_or(0));
+                state.apply_snapshot(&[], &[], ts);
             } else {
-                state = state.apply_delta(&[], &[], ev["ts"].as_i64().unwrap_or(0));
+                state.apply_delta(&[], &[], ts);
             }
-            let _ = out_tx.send(state.clone()).await;
+            let signal = match (&mut stopper, state.mid()) {
+                (Some(s), Some(mid)) => s.evaluate(vec![mid.to_f64()], 0.0, 0.0),
+                _ => StopSignal::Continue,
+            };
+            let _ = out_tx.send((state.clone(), signal)).await;
         }
     });
     (in_tx, out_rx)
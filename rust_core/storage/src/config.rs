@@ -0,0 +1,41 @@
+use crate::errors::StorageError;
+use std::env;
+
+/// Postgres connection parameters, read from the environment so deployment config lives outside
+/// the binary. `sslmode` is optional: unset (or `"disable"`) connects in plaintext; any other
+/// value (e.g. `"require"`) negotiates TLS via `postgres-native-tls` in [`crate::writer::connect`].
+#[derive(Debug, Clone)]
+pub struct PgConfig {
+    pub host: String,
+    pub port: u16,
+    pub dbname: String,
+    pub user: String,
+    pub password: Option<String>,
+    pub sslmode: Option<String>,
+}
+
+impl PgConfig {
+    /// Reads `PG_HOST`, `PG_PORT` (default `5432`), `PG_DB`, `PG_USER`, `PG_PASSWORD`, and
+    /// `PG_SSLMODE` from the environment. `PG_HOST`, `PG_DB`, and `PG_USER` are required.
+    pub fn from_env() -> Result<Self, StorageError> {
+        let host = env::var("PG_HOST").map_err(|_| StorageError::Config("PG_HOST not set".to_string()))?;
+        let dbname = env::var("PG_DB").map_err(|_| StorageError::Config("PG_DB not set".to_string()))?;
+        let user = env::var("PG_USER").map_err(|_| StorageError::Config("PG_USER not set".to_string()))?;
+        let port = env::var("PG_PORT").ok().and_then(|p| p.parse().ok()).unwrap_or(5432);
+        let password = env::var("PG_PASSWORD").ok();
+        let sslmode = env::var("PG_SSLMODE").ok().filter(|s| !s.is_empty() && s != "disable");
+        Ok(PgConfig { host, port, dbname, user, password, sslmode })
+    }
+
+    /// Renders the libpq key-value connection string `tokio_postgres::connect` expects.
+    pub fn connection_string(&self) -> String {
+        let mut s = format!("host={} port={} dbname={} user={}", self.host, self.port, self.dbname, self.user);
+        if let Some(pw) = &self.password {
+            s.push_str(&format!(" password={}", pw));
+        }
+        if let Some(mode) = &self.sslmode {
+            s.push_str(&format!(" sslmode={}", mode));
+        }
+        s
+    }
+}
@@ -0,0 +1,13 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum StorageError {
+    #[error("config error: {0}")]
+    Config(String),
+
+    #[error("connection error: {0}")]
+    Connection(String),
+
+    #[error("query error: {0}")]
+    Query(String),
+}
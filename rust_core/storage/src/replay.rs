@@ -0,0 +1,46 @@
+use crate::errors::StorageError;
+use connectors_common::types::MarketTick;
+use futures::{Stream, StreamExt};
+use log::warn;
+use tokio_postgres::types::ToSql;
+use tokio_postgres::Client;
+
+/// Reads `market_ticks` for `(exchange, pair)` in `[from_ts, to_ts)` back out in timestamp order,
+/// to drive `CandleAggregator::backfill` and arbitrage-backtest replay off durably stored history
+/// instead of a live feed. `ts` is stored as millis-since-epoch in a `bigint` column, which is
+/// exact for any timestamp in the lifetime of this system (`MarketTick::ts` is `u128` only to
+/// match other venue payload widths).
+pub async fn replay(
+    client: &Client,
+    exchange: &str,
+    pair: &str,
+    from_ts: u128,
+    to_ts: u128,
+) -> Result<impl Stream<Item = MarketTick> + '_, StorageError> {
+    let params: [&(dyn ToSql + Sync); 4] = [&exchange.to_string(), &pair.to_string(), &(from_ts as i64), &(to_ts as i64)];
+    let rows = client
+        .query_raw(
+            "SELECT exchange, pair, bid, ask, ts FROM market_ticks \
+             WHERE exchange = $1 AND pair = $2 AND ts >= $3 AND ts < $4 \
+             ORDER BY ts ASC",
+            params,
+        )
+        .await
+        .map_err(|e| StorageError::Query(e.to_string()))?;
+
+    Ok(rows.filter_map(|row| async move {
+        match row {
+            Ok(row) => Some(MarketTick {
+                exchange: row.get(0),
+                pair: row.get(1),
+                bid: row.get(2),
+                ask: row.get(3),
+                ts: row.get::<_, i64>(4) as u128,
+            }),
+            Err(e) => {
+                warn!("replay: skipping unreadable row: {}", e);
+                None
+            }
+        }
+    }))
+}
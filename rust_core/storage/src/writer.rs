@@ -0,0 +1,188 @@
+use crate::config::PgConfig;
+use crate::errors::StorageError;
+use connectors_common::types::{MarketTick, OrderBookSnapshot};
+use futures::SinkExt;
+use log::{info, warn};
+use tokio::sync::broadcast;
+use tokio::time::{interval, Duration};
+use tokio_postgres::{Client, NoTls};
+
+/// Flush thresholds for [`TickWriter`]: whichever fires first wins, so a quiet stream still
+/// lands rows within `flush_interval` and a busy one never grows a buffer past `max_rows`.
+#[derive(Debug, Clone)]
+pub struct BatchConfig {
+    pub max_rows: usize,
+    pub flush_interval: Duration,
+}
+
+impl Default for BatchConfig {
+    fn default() -> Self {
+        BatchConfig { max_rows: 5_000, flush_interval: Duration::from_millis(500) }
+    }
+}
+
+/// Connects to Postgres per `config` and spawns the driver task `tokio_postgres::Connection`
+/// requires to actually push bytes over the wire, returning just the `Client` handle writers and
+/// [`crate::replay::replay`] issue queries against.
+pub async fn connect(config: &PgConfig) -> Result<Client, StorageError> {
+    let conn_str = config.connection_string();
+    if config.sslmode.is_some() {
+        let tls = native_tls::TlsConnector::new().map_err(|e| StorageError::Connection(e.to_string()))?;
+        let tls = postgres_native_tls::MakeTlsConnector::new(tls);
+        let (client, connection) =
+            tokio_postgres::connect(&conn_str, tls).await.map_err(|e| StorageError::Connection(e.to_string()))?;
+        tokio::spawn(async move {
+            if let Err(e) = connection.await {
+                warn!("postgres connection error: {}", e);
+            }
+        });
+        Ok(client)
+    } else {
+        let (client, connection) =
+            tokio_postgres::connect(&conn_str, NoTls).await.map_err(|e| StorageError::Connection(e.to_string()))?;
+        tokio::spawn(async move {
+            if let Err(e) = connection.await {
+                warn!("postgres connection error: {}", e);
+            }
+        });
+        Ok(client)
+    }
+}
+
+/// Subscribes to an `Aggregator`'s tick broadcast (and, optionally, an
+/// [`L2Maintainer`](aggregator::l2::L2Maintainer)'s snapshot broadcast) and durably records both
+/// to `market_ticks` / `orderbook_snapshots` via batched `COPY` writes, so high tick rates don't
+/// force a round trip per row.
+pub struct TickWriter {
+    client: Client,
+    batch: BatchConfig,
+    tick_buf: Vec<MarketTick>,
+    snapshot_buf: Vec<OrderBookSnapshot>,
+}
+
+impl TickWriter {
+    pub fn new(client: Client, batch: BatchConfig) -> Self {
+        TickWriter { client, batch, tick_buf: Vec::new(), snapshot_buf: Vec::new() }
+    }
+
+    /// Drives the writer until the tick channel closes, flushing whichever buffer crosses
+    /// `batch.max_rows` immediately and both buffers every `batch.flush_interval` regardless.
+    /// `snapshots` is optional: pass `None` to record ticks only.
+    pub async fn run(
+        mut self,
+        mut ticks: broadcast::Receiver<MarketTick>,
+        mut snapshots: Option<broadcast::Receiver<OrderBookSnapshot>>,
+    ) {
+        let mut flush_tick = interval(self.batch.flush_interval);
+        loop {
+            tokio::select! {
+                res = ticks.recv() => match res {
+                    Ok(tick) => {
+                        self.tick_buf.push(tick);
+                        if self.tick_buf.len() >= self.batch.max_rows {
+                            self.flush_ticks().await;
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(n)) => info!("storage writer lagged, dropped {} ticks", n),
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+                res = recv_snapshot(&mut snapshots) => match res {
+                    Some(Ok(snap)) => {
+                        self.snapshot_buf.push(snap);
+                        if self.snapshot_buf.len() >= self.batch.max_rows {
+                            self.flush_snapshots().await;
+                        }
+                    }
+                    Some(Err(broadcast::error::RecvError::Lagged(n))) => info!("storage writer lagged, dropped {} snapshots", n),
+                    Some(Err(broadcast::error::RecvError::Closed)) | None => snapshots = None,
+                },
+                _ = flush_tick.tick() => {
+                    self.flush_ticks().await;
+                    self.flush_snapshots().await;
+                }
+            }
+        }
+        self.flush_ticks().await;
+        self.flush_snapshots().await;
+    }
+
+    async fn flush_ticks(&mut self) {
+        if self.tick_buf.is_empty() {
+            return;
+        }
+        if let Err(e) = copy_ticks(&self.client, &self.tick_buf).await {
+            warn!("failed to flush {} ticks: {}", self.tick_buf.len(), e);
+        }
+        self.tick_buf.clear();
+    }
+
+    async fn flush_snapshots(&mut self) {
+        if self.snapshot_buf.is_empty() {
+            return;
+        }
+        if let Err(e) = copy_snapshots(&self.client, &self.snapshot_buf).await {
+            warn!("failed to flush {} snapshots: {}", self.snapshot_buf.len(), e);
+        }
+        self.snapshot_buf.clear();
+    }
+}
+
+/// Awaits the next snapshot when a receiver is present; pends forever once it's been dropped so
+/// the enclosing `select!` stops polling that branch without needing a separate flag.
+async fn recv_snapshot(
+    rx: &mut Option<broadcast::Receiver<OrderBookSnapshot>>,
+) -> Option<Result<OrderBookSnapshot, broadcast::error::RecvError>> {
+    match rx {
+        Some(r) => Some(r.recv().await),
+        None => std::future::pending().await,
+    }
+}
+
+async fn copy_ticks(client: &Client, ticks: &[MarketTick]) -> Result<(), StorageError> {
+    let sink = client
+        .copy_in("COPY market_ticks (exchange, pair, bid, ask, ts) FROM STDIN WITH (FORMAT csv)")
+        .await
+        .map_err(|e| StorageError::Query(e.to_string()))?;
+    futures::pin_mut!(sink);
+    let mut csv = String::new();
+    for t in ticks {
+        csv.push_str(&format!("{},{},{},{},{}\n", csv_escape(&t.exchange), csv_escape(&t.pair), t.bid, t.ask, t.ts));
+    }
+    sink.send(bytes::Bytes::from(csv)).await.map_err(|e| StorageError::Query(e.to_string()))?;
+    sink.finish().await.map_err(|e| StorageError::Query(e.to_string()))?;
+    Ok(())
+}
+
+async fn copy_snapshots(client: &Client, snapshots: &[OrderBookSnapshot]) -> Result<(), StorageError> {
+    let sink = client
+        .copy_in("COPY orderbook_snapshots (exchange, pair, bids, asks, ts) FROM STDIN WITH (FORMAT csv)")
+        .await
+        .map_err(|e| StorageError::Query(e.to_string()))?;
+    futures::pin_mut!(sink);
+    let mut csv = String::new();
+    for s in snapshots {
+        // bids/asks land in jsonb columns; ts is millis since epoch, see the note on `ts` in
+        // crate::replay.
+        let bids = serde_json::to_string(&s.bids).unwrap_or_default();
+        let asks = serde_json::to_string(&s.asks).unwrap_or_default();
+        csv.push_str(&format!(
+            "{},{},{},{},{}\n",
+            csv_escape(&s.exchange),
+            csv_escape(&s.pair),
+            csv_escape(&bids),
+            csv_escape(&asks),
+            s.ts
+        ));
+    }
+    sink.send(bytes::Bytes::from(csv)).await.map_err(|e| StorageError::Query(e.to_string()))?;
+    sink.finish().await.map_err(|e| StorageError::Query(e.to_string()))?;
+    Ok(())
+}
+
+fn csv_escape(s: &str) -> String {
+    if s.contains(',') || s.contains('"') || s.contains('\n') {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
@@ -1,12 +1,13 @@
 use pyo3::prelude::*;
 use pyo3::types::PyDict;
 use pyo3_asyncio::tokio::future_into_py;
+use connectors_common::connector::Connector;
 use connectors_common::types::MarketTick;
 use aggregator::Aggregator;
 use tokio::sync::{mpsc, broadcast, oneshot};
 use std::sync::{Arc, Mutex};
 use std::collections::HashMap;
-use log::info;
+use log::warn;
 
 type HandleId = u64;
 
@@ -21,6 +22,26 @@ struct PyAggregator {
     next_handle: Arc<Mutex<HandleId>>,
 }
 
+impl PyAggregator {
+    /// Spawns `connector.run` wired to a real `stop_rx`, so the returned handle's
+    /// `stop_connector` call actually tears the feed down instead of being ignored.
+    fn start<C: Connector + 'static>(&self, connector: C) -> u64 {
+        let tx = self.inner.create_input_channel(1024);
+        let (stop_tx, stop_rx) = oneshot::channel();
+        let name = connector.name().to_string();
+        tokio::spawn(async move {
+            if let Err(e) = connector.run(tx, stop_rx).await {
+                warn!("connector {} stopped with error: {:?}", name, e);
+            }
+        });
+        let mut nh = self.next_handle.lock().unwrap();
+        let id = *nh;
+        *nh += 1;
+        self.handles.lock().unwrap().insert(id, ConnectorHandle { stop_tx });
+        id
+    }
+}
+
 #[pymethods]
 impl PyAggregator {
     #[new]
@@ -72,56 +93,20 @@ impl PyAggregator {
     }
 
     fn start_binance_ws(&self, _pairs: Option<Vec<String>>) -> PyResult<u64> {
-        let tx = self.inner.create_input_channel(1024);
-        let (stop_tx, _stop_rx) = oneshot::channel();
-        tokio::spawn(async move {
-            let _ = connector_binance::ws::run_binance_ws(tx).await;
-        });
-        let mut nh = self.next_handle.lock().unwrap();
-        let id = *nh;
-        *nh += 1;
-        self.handles.lock().unwrap().insert(id, ConnectorHandle { stop_tx });
-        Ok(id)
+        Ok(self.start(connector_binance::ws::BinanceWsConnector))
     }
 
     fn start_kraken_ws(&self, _pairs: Option<Vec<String>>) -> PyResult<u64> {
-        let tx = self.inner.create_input_channel(1024);
-        let (stop_tx, _stop_rx) = oneshot::channel();
-        tokio::spawn(async move {
-            let _ = connector_kraken::ws::run_kraken_ws(tx).await;
-        });
-        let mut nh = self.next_handle.lock().unwrap();
-        let id = *nh;
-        *nh += 1;
-        self.handles.lock().unwrap().insert(id, ConnectorHandle { stop_tx });
-        Ok(id)
+        Ok(self.start(connector_kraken::ws::KrakenWsConnector))
     }
 
     fn start_coinbase_ws(&self, _pairs: Option<Vec<String>>) -> PyResult<u64> {
-        let tx = self.inner.create_input_channel(1024);
-        let (stop_tx, _stop_rx) = oneshot::channel();
-        tokio::spawn(async move {
-            let _ = connector_coinbase::ws::run_coinbase_ws(tx).await;
-        });
-        let mut nh = self.next_handle.lock().unwrap();
-        let id = *nh;
-        *nh += 1;
-        self.handles.lock().unwrap().insert(id, ConnectorHandle { stop_tx });
-        Ok(id)
+        Ok(self.start(connector_coinbase::ws::CoinbaseWsConnector))
     }
 
     fn start_coingecko_poll(&self, pairs: Vec<String>, interval_ms: Option<u64>) -> PyResult<u64> {
-        let tx = self.inner.create_input_channel(1024);
-        let (stop_tx, _stop_rx) = oneshot::channel();
-        let int_ms = interval_ms.unwrap_or(5000);
-        tokio::spawn(async move {
-            let _ = connector_coingecko::rest::run_coingecko_poll(tx, pairs, int_ms).await;
-        });
-        let mut nh = self.next_handle.lock().unwrap();
-        let id = *nh;
-        *nh += 1;
-        self.handles.lock().unwrap().insert(id, ConnectorHandle { stop_tx });
-        Ok(id)
+        let interval_ms = interval_ms.unwrap_or(5000);
+        Ok(self.start(connector_coingecko::rest::CoingeckoConnector { pairs, interval_ms }))
     }
 
     fn stop_connector(&self, handle: u64) -> PyResult<bool> {